@@ -0,0 +1,41 @@
+//! Compares sequential and parallel block entropy.
+//!
+//! Run with `cargo bench --features rayon --bench block_entropy`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use shannon::{block_entropies, par_block_entropy};
+
+const SIZE: usize = 256 * 1024 * 1024;
+const BLOCK_SIZE: usize = 4096;
+
+fn main() {
+    let mut state = 0x2545F4914F6CDD1D_u64;
+    let data: Vec<u8> = (0..SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u8
+        })
+        .collect();
+
+    let start = Instant::now();
+    let sequential = block_entropies::<f64>(black_box(&data), BLOCK_SIZE);
+    let sequential_time = start.elapsed();
+    black_box(&sequential);
+
+    let start = Instant::now();
+    let parallel = par_block_entropy::<f64>(black_box(&data), BLOCK_SIZE);
+    let parallel_time = start.elapsed();
+    black_box(&parallel);
+
+    assert_eq!(sequential, parallel);
+    println!(
+        "{} MiB in {BLOCK_SIZE} byte blocks: sequential {sequential_time:?}, parallel {parallel_time:?} ({} threads, {:.1}x)",
+        SIZE / (1024 * 1024),
+        rayon::current_num_threads(),
+        sequential_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+}
@@ -0,0 +1,57 @@
+//! Compares single table and interleaved byte counting.
+//!
+//! Run with `cargo bench --features simd --bench byte_histogram`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use shannon::{byte_histogram_interleaved, entropy};
+
+const SIZE: usize = 256 * 1024 * 1024;
+
+/// The counting loop `byte_histogram` uses without the `simd` feature.
+fn single_table(data: &[u8]) -> [usize; 256] {
+    let mut counts = [0usize; 256];
+    for byte in data {
+        counts[*byte as usize] += 1;
+    }
+    counts
+}
+
+fn main() {
+    let mut state = 0x2545F4914F6CDD1D_u64;
+    let random: Vec<u8> = (0..SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u8
+        })
+        .collect();
+    let zeros = vec![0u8; SIZE];
+
+    for (name, data) in [("random", &random), ("zeros", &zeros)] {
+        let start = Instant::now();
+        let single = single_table(black_box(data));
+        let single_time = start.elapsed();
+
+        let start = Instant::now();
+        let interleaved = byte_histogram_interleaved(black_box(data));
+        let interleaved_time = start.elapsed();
+
+        assert_eq!(single, interleaved);
+        println!(
+            "{} MiB of {name}: single table {single_time:?}, interleaved {interleaved_time:?} ({:.2}x)",
+            SIZE / (1024 * 1024),
+            single_time.as_secs_f64() / interleaved_time.as_secs_f64()
+        );
+    }
+
+    let start = Instant::now();
+    let e: f64 = entropy(black_box(&random));
+    println!(
+        "entropy of {} MiB: {e:.6} in {:?}",
+        SIZE / (1024 * 1024),
+        start.elapsed()
+    );
+}
@@ -0,0 +1,49 @@
+//! Times `entropy` on degenerate inputs that take its fast paths: a buffer of
+//! a single repeated byte, the same buffer ending in one different byte, and
+//! short blocks of distinct bytes.
+//!
+//! Run with `cargo bench --bench degenerate`.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use shannon::entropy;
+
+const SIZE: usize = 1024 * 1024 * 1024;
+const DISTINCT_ROUNDS: usize = 1_000_000;
+
+fn main() {
+    let zeros = vec![0u8; SIZE];
+    let start = Instant::now();
+    let e: f64 = entropy(black_box(&zeros));
+    assert_eq!(e, 0.0);
+    println!(
+        "{} MiB of zeros: entropy {:?}",
+        SIZE / (1024 * 1024),
+        start.elapsed()
+    );
+
+    let mut zeros = zeros;
+    zeros[SIZE - 1] = 1;
+    let start = Instant::now();
+    let e: f64 = entropy(black_box(&zeros));
+    assert!(e > 0.0);
+    println!(
+        "{} MiB of zeros then one different byte: entropy {:?}",
+        SIZE / (1024 * 1024),
+        start.elapsed()
+    );
+
+    let distinct: Vec<u8> = (0..=255).collect();
+    for len in [16, 256] {
+        let start = Instant::now();
+        let total: f64 = (0..DISTINCT_ROUNDS)
+            .map(|_| entropy::<f64>(black_box(&distinct[..len])))
+            .sum();
+        assert!((total / DISTINCT_ROUNDS as f64 - (len as f64).log2()).abs() < 1e-9);
+        println!(
+            "{DISTINCT_ROUNDS} blocks of {len} distinct bytes: entropy {:?}",
+            start.elapsed()
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! Compares `entropy` with the log table based `entropy_fast`.
+//!
+//! Run with `cargo bench --bench entropy`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use shannon::{entropy, entropy_fast};
+
+const SIZE: usize = 64 * 1024 * 1024;
+const BLOCK_SIZES: [usize; 4] = [64, 256, 4096, 65536];
+
+fn time(data: &[u8], block_size: usize, f: fn(&[u8]) -> f64) -> (Duration, f64) {
+    let start = Instant::now();
+    let total: f64 = data.chunks(block_size).map(|c| f(black_box(c))).sum();
+    (start.elapsed(), total)
+}
+
+fn main() {
+    let mut state = 0x2545F4914F6CDD1D_u64;
+    let data: Vec<u8> = (0..SIZE)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state >> 32) as u8
+        })
+        .collect();
+
+    for block_size in BLOCK_SIZES {
+        let (current_time, current) = time(&data, block_size, entropy);
+        let (fast_time, fast) = time(&data, block_size, entropy_fast);
+        let blocks = SIZE.div_ceil(block_size) as f64;
+        assert!((current - fast).abs() / blocks < 1e-9);
+        println!(
+            "{} MiB in {block_size} byte blocks: entropy {current_time:?}, entropy_fast {fast_time:?} ({:.2}x)",
+            SIZE / (1024 * 1024),
+            current_time.as_secs_f64() / fast_time.as_secs_f64()
+        );
+    }
+}
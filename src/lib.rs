@@ -3,17 +3,51 @@
 //! Provides functions for calculating Shannon entropy of byte sequences,
 //! useful for analyzing randomness and information density in data.
 
-use num_traits::{Float, FromPrimitive};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("either the `std` or `libm` feature must be enabled");
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign};
+use num_traits::{Float, FromPrimitive, One, ToPrimitive, Zero};
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::io::{self, Read};
 
 /// Represents the type of entropy edge detected.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Rising edges order before falling edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum EdgeType {
     Rising,
     Falling,
 }
 
+impl fmt::Display for EdgeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            EdgeType::Rising => "Rising",
+            EdgeType::Falling => "Falling",
+        })
+    }
+}
+
 /// Represents a detected entropy edge in a sequence of entropy values.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EntropyEdge<F> {
     /// Block index where the edge was detected
     pub block_index: usize,
@@ -21,6 +55,95 @@ pub struct EntropyEdge<F> {
     pub edge_type: EdgeType,
     /// Normalized entropy value (0.0 to 1.0) at this edge
     pub entropy: F,
+    /// Entropy value at this edge as passed to the detector, in bits per byte
+    /// (0.0 to 8.0) unless a different maximum entropy was used
+    pub entropy_bits: F,
+    /// Change in normalized entropy from the previous block, 0.0 if this
+    /// edge is on the first block
+    pub slope: F,
+}
+
+/// Formats an edge as e.g. `Rising edge at block 42 (entropy 0.97)`, where
+/// the entropy is normalized and printed with the formatter's precision if
+/// one is given.
+impl<F: fmt::Display> fmt::Display for EntropyEdge<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} edge at block {} (entropy ",
+            self.edge_type, self.block_index
+        )?;
+        match f.precision() {
+            Some(precision) => write!(f, "{:.precision$})", self.entropy),
+            None => write!(f, "{})", self.entropy),
+        }
+    }
+}
+
+impl<F> EntropyEdge<F> {
+    /// Returns the byte offset of the start of the edge's block, i.e.
+    /// `block_index * block_size`, saturating at `usize::MAX`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use shannon::detect_edges;
+    ///
+    /// let values = vec![(0, 1.0_f64), (1, 7.9)];
+    /// let edges = detect_edges(&values, 0.95, 0.85);
+    /// assert_eq!(edges[1].byte_offset(1024), 1024);
+    /// ```
+    pub const fn byte_offset(&self, block_size: usize) -> usize {
+        self.block_index.saturating_mul(block_size)
+    }
+}
+
+/// Sorts entropy edges by block index, e.g. after merging edges from several
+/// detectors.
+///
+/// Edges at the same block index are ordered rising before falling, then by
+/// ascending normalized entropy, with NaN entropies after all others. Edges
+/// that still compare equal, including two NaN entropies, keep their original
+/// order, so exact duplicates end up adjacent and can be removed with
+/// [`Vec::dedup`] (which never removes NaN edges, as NaN is not equal to
+/// itself).
+///
+/// # Arguments
+///
+/// * `edges` - The edges to sort in place
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_edges, sort_edges};
+///
+/// let mut edges = detect_edges(&[(0, 1.0_f64), (1, 7.9)], 0.95, 0.85);
+/// edges.extend(detect_edges(&[(0, 1.0_f64), (1, 7.9)], 0.95, 0.85));
+/// sort_edges(&mut edges);
+/// edges.dedup();
+/// assert_eq!(edges.len(), 2);
+/// assert_eq!(edges[0].block_index, 0);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn sort_edges<F: Float>(edges: &mut [EntropyEdge<F>]) {
+    edges.sort_by(|a, b| {
+        a.block_index
+            .cmp(&b.block_index)
+            .then(a.edge_type.cmp(&b.edge_type))
+            .then(cmp_nan_last(a.entropy, b.entropy))
+    });
+}
+
+/// Totally orders floats as by `partial_cmp`, with every NaN equal to each
+/// other and greater than any number.
+#[cfg(feature = "alloc")]
+fn cmp_nan_last<F: Float>(a: F, b: F) -> core::cmp::Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => core::cmp::Ordering::Equal,
+        (true, false) => core::cmp::Ordering::Greater,
+        (false, true) => core::cmp::Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
 }
 
 /// Detects rising and falling edges in a sequence of entropy values.
@@ -52,102 +175,520 @@ pub struct EntropyEdge<F> {
 /// assert_eq!(edges[0].block_index, 0);
 /// assert_eq!(edges[1].edge_type, EdgeType::Falling);
 /// assert_eq!(edges[1].block_index, 2);
+/// assert_eq!(edges[1].entropy_bits, 2.0);
 /// ```
+#[cfg(feature = "alloc")]
 pub fn detect_edges<F: Float + FromPrimitive>(
     entropy_values: &[(usize, F)],
     high_threshold: F,
     low_threshold: F,
 ) -> Vec<EntropyEdge<F>> {
-    let eight = F::from_f64(8.0).unwrap();
-    let mut edges = Vec::new();
-    let mut last_edge: Option<bool> = None;
-    let mut trigger_reset = true;
+    detect_edges_normalized(
+        entropy_values,
+        high_threshold,
+        low_threshold,
+        F::from_f64(8.0).unwrap(),
+    )
+}
 
-    for &(block_index, entropy) in entropy_values {
-        let normalized = entropy / eight;
+/// Detects rising and falling edges in entropy values with a given maximum.
+///
+/// Behaves like [`detect_edges`], but normalizes entropy values by dividing
+/// by `max_entropy` instead of 8, for values computed over a smaller alphabet
+/// or in a different base. [`detect_edges`] is this function with a
+/// `max_entropy` of 8.0.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in `0..=max_entropy`
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+/// * `max_entropy` - The largest possible entropy value, used for normalization
+///
+/// # Returns
+///
+/// A vector of detected entropy edges
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_edges_normalized, EdgeType};
+///
+/// // Entropy of DNA sequences in bits (0-2)
+/// let values = vec![(0, 0.5_f64), (1, 1.98), (2, 1.99), (3, 0.4)];
+/// let edges = detect_edges_normalized(&values, 0.95, 0.85, 2.0);
+/// assert_eq!(edges.len(), 3);
+/// assert_eq!(edges[1].edge_type, EdgeType::Rising);
+/// assert_eq!(edges[1].entropy, 0.99);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_edges_normalized<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+    max_entropy: F,
+) -> Vec<EntropyEdge<F>> {
+    let mut detector = EdgeDetector::with_max_entropy(high_threshold, low_threshold, max_entropy);
+    entropy_values
+        .iter()
+        .filter_map(|&(block_index, entropy)| detector.push(block_index, entropy))
+        .collect()
+}
 
-        if (matches!(last_edge, None | Some(false)) && normalized > low_threshold)
-            || (matches!(last_edge, Some(true)) && normalized < high_threshold)
-        {
-            trigger_reset = true;
-        }
+/// Lazily detects rising and falling edges in an iterator of entropy values.
+///
+/// Behaves like [`detect_edges`], but consumes `entropy_values` as the
+/// returned iterator is advanced rather than requiring a slice.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Iterator of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// An iterator over the detected entropy edges
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_edges_iter, EdgeType};
+///
+/// let values = [7.8_f64, 7.9, 2.0, 1.0].into_iter().enumerate();
+/// let mut edges = detect_edges_iter(values, 0.95, 0.85);
+/// assert_eq!(edges.next().unwrap().edge_type, EdgeType::Rising);
+/// assert_eq!(edges.next().unwrap().block_index, 2);
+/// assert!(edges.next().is_none());
+/// ```
+pub fn detect_edges_iter<F, I>(
+    entropy_values: I,
+    high_threshold: F,
+    low_threshold: F,
+) -> impl Iterator<Item = EntropyEdge<F>>
+where
+    F: Float + FromPrimitive,
+    I: IntoIterator<Item = (usize, F)>,
+{
+    let mut detector = EdgeDetector::new(high_threshold, low_threshold);
+    entropy_values
+        .into_iter()
+        .filter_map(move |(block_index, entropy)| detector.push(block_index, entropy))
+}
 
-        if trigger_reset && normalized >= high_threshold {
-            edges.push(EntropyEdge {
-                block_index,
-                edge_type: EdgeType::Rising,
-                entropy: normalized,
-            });
-            last_edge = Some(true);
-            trigger_reset = false;
-        } else if trigger_reset && normalized <= low_threshold {
-            edges.push(EntropyEdge {
-                block_index,
-                edge_type: EdgeType::Falling,
-                entropy: normalized,
-            });
-            last_edge = Some(false);
-            trigger_reset = false;
+/// Detects rising and falling edges scanning from the last block to the first.
+///
+/// Behaves like [`detect_edges`] run on the reversed series, so the hysteresis
+/// state builds up from the end of the data. Edge types and slopes describe
+/// the change as seen walking backwards: the last block of a high entropy
+/// region followed by low entropy data is reported as a rising edge, and
+/// `slope` is the change from the block after it. Block indices are those
+/// of `entropy_values`, and edges are returned in descending block order, so
+/// the first edge is the transition closest to the end.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// A vector of detected entropy edges, last block first
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_edges_reverse, EdgeType};
+///
+/// // An encrypted region in blocks 1 to 2, followed by padding
+/// let values = vec![(0, 1.0_f64), (1, 7.9), (2, 7.9), (3, 0.0), (4, 0.0)];
+/// let edges = detect_edges_reverse(&values, 0.95, 0.85);
+/// assert_eq!(edges[0].edge_type, EdgeType::Falling);
+/// assert_eq!(edges[0].block_index, 4);
+/// assert_eq!(edges[1].edge_type, EdgeType::Rising);
+/// assert_eq!(edges[1].block_index, 2);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_edges_reverse<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+) -> Vec<EntropyEdge<F>> {
+    detect_edges_iter(
+        entropy_values.iter().rev().copied(),
+        high_threshold,
+        low_threshold,
+    )
+    .collect()
+}
+
+/// Detects rising and falling edges, ignoring crossings shorter than
+/// `min_blocks`.
+///
+/// Behaves like [`detect_edges`], except an edge is only confirmed if the
+/// entropy stays at or beyond the crossed threshold for at least `min_blocks`
+/// consecutive blocks. The reported edge is the first block of that run. A
+/// crossing too close to the end of `entropy_values` to be confirmed is not
+/// reported. A `min_blocks` of 0 or 1 is identical to [`detect_edges`].
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+/// * `min_blocks` - Number of consecutive blocks needed to confirm an edge
+///
+/// # Returns
+///
+/// A vector of detected entropy edges
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_edges_with_dwell, EdgeType};
+///
+/// // A single-block spike followed by a sustained rise
+/// let values = vec![(0, 1.0_f64), (1, 1.0), (2, 7.9), (3, 1.0), (4, 7.9), (5, 7.9)];
+/// let edges = detect_edges_with_dwell(&values, 0.95, 0.85, 2);
+/// assert_eq!(edges.len(), 2);
+/// assert_eq!(edges[0].edge_type, EdgeType::Falling);
+/// assert_eq!(edges[1].edge_type, EdgeType::Rising);
+/// assert_eq!(edges[1].block_index, 4);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_edges_with_dwell<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+    min_blocks: usize,
+) -> Vec<EntropyEdge<F>> {
+    dwell_edges(
+        entropy_values,
+        EdgeDetector::new(high_threshold, low_threshold),
+        min_blocks,
+    )
+}
+
+/// Runs `detector` over `entropy_values`, confirming each edge only if the
+/// entropy stays beyond the crossed threshold for `min_blocks` blocks.
+#[cfg(feature = "alloc")]
+fn dwell_edges<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    mut detector: EdgeDetector<F>,
+    min_blocks: usize,
+) -> Vec<EntropyEdge<F>> {
+    let max_entropy = detector.max_entropy;
+    let (high_threshold, low_threshold) = (detector.high_threshold, detector.low_threshold);
+    let min_blocks = min_blocks.max(1);
+    let mut edges = Vec::new();
+
+    for (i, &(block_index, entropy)) in entropy_values.iter().enumerate() {
+        let normalized = entropy / max_entropy;
+        let run = entropy_values.get(i..i + min_blocks);
+        let stays_high =
+            || run.is_some_and(|run| run.iter().all(|&(_, e)| e / max_entropy >= high_threshold));
+        let stays_low =
+            || run.is_some_and(|run| run.iter().all(|&(_, e)| e / max_entropy <= low_threshold));
+
+        let confirmed = match detector.candidate(normalized) {
+            Some(EdgeType::Rising) => stays_high(),
+            Some(EdgeType::Falling) => stays_low(),
+            None => false,
+        };
+        if confirmed {
+            edges.extend(detector.push(block_index, entropy));
+        } else {
+            detector.previous = Some(normalized);
         }
     }
 
     edges
 }
 
-/// Calculates the Shannon entropy of a byte slice.
+/// Detects rising and falling edges relative to a moving average.
 ///
-/// Shannon entropy measures the average information content per byte,
-/// ranging from 0 (completely uniform) to 8 (maximum randomness).
+/// Instead of fixed thresholds, each block is compared with the mean
+/// normalized entropy of the up to `window` blocks before it. A rising edge is
+/// detected when the block is at least `rising_delta` above that mean, and a
+/// falling edge when it is at least `falling_delta` below it. As in
+/// [`detect_edges`], rising and falling edges alternate, so a sustained change
+/// is reported once. The first block has no history and never produces an
+/// edge.
 ///
 /// # Arguments
 ///
-/// * `data` - A byte slice to analyze
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `rising_delta` - Normalized rise (0.0-1.0) above the moving average for a rising edge
+/// * `falling_delta` - Normalized fall (0.0-1.0) below the moving average for a falling edge
+/// * `window` - Number of preceding blocks in the moving average
 ///
 /// # Returns
 ///
-/// The entropy value in bits per byte (0.0 to 8.0)
+/// A vector of detected entropy edges
 ///
-/// # Example
+/// # Panics
 ///
-/// ## Calculating entropy of a Vec
+/// Panics if `window` is 0.
+///
+/// # Example
 ///
 /// ```
-/// use shannon::entropy;
+/// use shannon::{detect_edges_adaptive, EdgeType};
 ///
-/// let uniform_data = vec![0u8; 100];
-/// let e: f64 = entropy(&uniform_data);
-/// assert_eq!(e, 0.0);
+/// // Never reaches 0.95 normalized, but steps up from 3 to 5 bits and back
+/// let values = vec![(0, 3.0_f64), (1, 3.0), (2, 5.0), (3, 5.0), (4, 5.0), (5, 3.0)];
+/// let edges = detect_edges_adaptive(&values, 0.2, 0.2, 2);
+/// assert_eq!(edges.len(), 2);
+/// assert_eq!(edges[0].edge_type, EdgeType::Rising);
+/// assert_eq!(edges[0].block_index, 2);
+/// assert_eq!(edges[1].edge_type, EdgeType::Falling);
+/// assert_eq!(edges[1].block_index, 5);
 /// ```
+#[cfg(feature = "alloc")]
+pub fn detect_edges_adaptive<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    rising_delta: F,
+    falling_delta: F,
+    window: usize,
+) -> Vec<EntropyEdge<F>> {
+    assert!(window > 0, "window must be non-zero");
+    let eight = F::from_f64(8.0).unwrap();
+    let mut last_edge = None;
+    let mut edges = Vec::new();
+
+    for (i, &(block_index, entropy)) in entropy_values.iter().enumerate().skip(1) {
+        let normalized = entropy / eight;
+        let history = &entropy_values[i.saturating_sub(window)..i];
+        let mean = history
+            .iter()
+            .fold(F::zero(), |sum, &(_, e)| sum + e / eight)
+            / F::from_usize(history.len()).unwrap();
+
+        let edge_type = if last_edge != Some(EdgeType::Rising) && normalized - mean >= rising_delta
+        {
+            EdgeType::Rising
+        } else if last_edge != Some(EdgeType::Falling) && mean - normalized >= falling_delta {
+            EdgeType::Falling
+        } else {
+            continue;
+        };
+        last_edge = Some(edge_type);
+        edges.push(EntropyEdge {
+            block_index,
+            edge_type,
+            entropy: normalized,
+            entropy_bits: entropy,
+            slope: normalized - entropy_values[i - 1].1 / eight,
+        });
+    }
+
+    edges
+}
+
+/// Detects entropy edges one block at a time.
 ///
-/// ## Calculating entropy per character of a String
+/// Holds the same hysteresis state as [`detect_edges`], so values can be
+/// pushed as they are calculated without collecting the whole series first.
+///
+/// # Example
 ///
 /// ```
-/// use shannon::entropy;
+/// use shannon::{EdgeDetector, EdgeType};
 ///
-/// let text = String::from("AABB");
-/// let e: f64 = entropy(text.as_bytes());
-/// assert_eq!(e, 1.0);
+/// let mut detector = EdgeDetector::new(0.95_f64, 0.85);
+/// assert_eq!(detector.push(0, 7.9).unwrap().edge_type, EdgeType::Rising);
+/// assert!(detector.push(1, 7.8).is_none());
+/// assert_eq!(detector.push(2, 1.0).unwrap().edge_type, EdgeType::Falling);
 /// ```
-pub fn entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
-    let data_len = F::from_usize(data.len()).unwrap();
-    let mut counts = [0usize; 256];
-    for byte in data {
-        counts[*byte as usize] += 1;
+#[derive(Debug, Clone)]
+pub struct EdgeDetector<F> {
+    high_threshold: F,
+    low_threshold: F,
+    last_edge: Option<bool>,
+    trigger_reset: bool,
+    previous: Option<F>,
+    max_entropy: F,
+}
+
+impl<F: Float + FromPrimitive> EdgeDetector<F> {
+    /// Creates a detector with normalized (0.0-1.0) rising and falling
+    /// thresholds.
+    pub fn new(high_threshold: F, low_threshold: F) -> Self {
+        Self::with_max_entropy(high_threshold, low_threshold, F::from_f64(8.0).unwrap())
     }
-    let mut entropy = F::zero();
-    for count in counts {
-        if count == 0 {
-            continue;
+
+    /// Creates a detector that normalizes entropy values by dividing by
+    /// `max_entropy` instead of 8.
+    pub fn with_max_entropy(high_threshold: F, low_threshold: F, max_entropy: F) -> Self {
+        Self {
+            high_threshold,
+            low_threshold,
+            last_edge: None,
+            trigger_reset: true,
+            previous: None,
+            max_entropy,
+        }
+    }
+
+    /// Feeds the entropy in bits (0-8) of the next block to the detector.
+    ///
+    /// # Returns
+    ///
+    /// The edge detected on this block, if any
+    pub fn push(&mut self, block_index: usize, entropy: F) -> Option<EntropyEdge<F>> {
+        self.push_only(block_index, entropy, None)
+    }
+
+    /// Feeds the next block like [`EdgeDetector::push`], but only builds an
+    /// edge of type `only`; edges of the other type just advance the
+    /// hysteresis.
+    fn push_only(
+        &mut self,
+        block_index: usize,
+        entropy: F,
+        only: Option<EdgeType>,
+    ) -> Option<EntropyEdge<F>> {
+        let normalized = entropy / self.max_entropy;
+        let edge_type = self.candidate(normalized);
+        if let Some(edge_type) = edge_type {
+            self.last_edge = Some(edge_type == EdgeType::Rising);
+            self.trigger_reset = false;
+        }
+        let edge = edge_type
+            .filter(|&edge_type| only.is_none_or(|only| only == edge_type))
+            .map(|edge_type| EntropyEdge {
+                block_index,
+                edge_type,
+                entropy: normalized,
+                entropy_bits: entropy,
+                slope: self.previous.map_or(F::zero(), |prev| normalized - prev),
+            });
+        self.previous = Some(normalized);
+        edge
+    }
+
+    /// Re-arms the trigger if needed and returns the edge that `normalized`
+    /// would produce, without recording it.
+    fn candidate(&mut self, normalized: F) -> Option<EdgeType> {
+        if (matches!(self.last_edge, None | Some(false)) && normalized > self.low_threshold)
+            || (matches!(self.last_edge, Some(true)) && normalized < self.high_threshold)
+        {
+            self.trigger_reset = true;
+        }
+
+        if self.trigger_reset && normalized >= self.high_threshold {
+            Some(EdgeType::Rising)
+        } else if self.trigger_reset && normalized <= self.low_threshold {
+            Some(EdgeType::Falling)
+        } else {
+            None
         }
-        let p_x = F::from_usize(count).unwrap() / data_len;
-        entropy = entropy - p_x * p_x.log2();
     }
-    entropy
 }
-/// Calculates the total Shannon entropy of a byte slice.
+
+/// Configures edge detection beyond the arguments of [`detect_edges`].
 ///
-/// Shannon entropy measures the average information content per byte,
-/// multiplied by the length this returns the total entropy of the byte slice.
+/// Combines the options of [`detect_edges_with_dwell`],
+/// [`detect_edges_normalized`] and [`detect_rising_edges`] /
+/// [`detect_falling_edges`]. The defaults are a high threshold of 0.95, a low
+/// threshold of 0.85, no dwell, a maximum entropy of 8.0 and both edge types,
+/// which gives the same edges as `detect_edges(values, 0.95, 0.85)`.
+///
+/// # Example
+///
+/// ```
+/// use shannon::{EdgeDetectorBuilder, EdgeType};
+///
+/// let values = vec![(0, 1.0_f64), (1, 7.9), (2, 1.0), (3, 7.9), (4, 7.9)];
+/// let edges = EdgeDetectorBuilder::new()
+///     .min_dwell(2)
+///     .only(EdgeType::Rising)
+///     .detect(&values);
+/// assert_eq!(edges.len(), 1);
+/// assert_eq!(edges[0].block_index, 3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeDetectorBuilder<F> {
+    high_threshold: F,
+    low_threshold: F,
+    min_dwell: usize,
+    max_entropy: F,
+    only: Option<EdgeType>,
+}
+
+impl<F: Float + FromPrimitive> Default for EdgeDetectorBuilder<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Float + FromPrimitive> EdgeDetectorBuilder<F> {
+    /// Creates a builder with the default configuration.
+    pub fn new() -> Self {
+        Self {
+            high_threshold: F::from_f64(0.95).unwrap(),
+            low_threshold: F::from_f64(0.85).unwrap(),
+            min_dwell: 1,
+            max_entropy: F::from_f64(8.0).unwrap(),
+            only: None,
+        }
+    }
+
+    /// Sets the normalized (0.0-1.0) threshold for rising edges.
+    pub fn high_threshold(mut self, high_threshold: F) -> Self {
+        self.high_threshold = high_threshold;
+        self
+    }
+
+    /// Sets the normalized (0.0-1.0) threshold for falling edges.
+    pub fn low_threshold(mut self, low_threshold: F) -> Self {
+        self.low_threshold = low_threshold;
+        self
+    }
+
+    /// Sets the number of consecutive blocks needed to confirm an edge, as in
+    /// [`detect_edges_with_dwell`].
+    pub fn min_dwell(mut self, min_blocks: usize) -> Self {
+        self.min_dwell = min_blocks;
+        self
+    }
+
+    /// Sets the largest possible entropy value, used for normalization, as
+    /// in [`detect_edges_normalized`].
+    pub fn max_entropy(mut self, max_entropy: F) -> Self {
+        self.max_entropy = max_entropy;
+        self
+    }
+
+    /// Only reports edges of `edge_type`. Both types are still tracked by
+    /// the hysteresis.
+    pub fn only(mut self, edge_type: EdgeType) -> Self {
+        self.only = Some(edge_type);
+        self
+    }
+
+    /// Detects edges in `entropy_values` with this configuration.
+    ///
+    /// # Returns
+    ///
+    /// A vector of detected entropy edges
+    #[cfg(feature = "alloc")]
+    pub fn detect(&self, entropy_values: &[(usize, F)]) -> Vec<EntropyEdge<F>> {
+        let detector = EdgeDetector::with_max_entropy(
+            self.high_threshold,
+            self.low_threshold,
+            self.max_entropy,
+        );
+        let mut edges = dwell_edges(entropy_values, detector, self.min_dwell);
+        if let Some(edge_type) = self.only {
+            edges.retain(|edge| edge.edge_type == edge_type);
+        }
+        edges
+    }
+}
+
+/// Counts the occurrences of each byte value in a byte slice.
 ///
 /// # Arguments
 ///
@@ -155,30 +696,3715 @@ pub fn entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
 ///
 /// # Returns
 ///
-/// The total entropy of data
+/// An array where index `i` holds the number of times byte `i` occurs
 ///
 /// # Example
 ///
-/// ## Calculating total entropy of a String
-///
 /// ```
-/// use shannon::total_entropy;
+/// use shannon::byte_histogram;
 ///
-/// let text = String::from("AABB");
-/// let e = total_entropy::<f64>(text.as_bytes());
-/// assert_eq!(e, 4.0);
+/// let counts = byte_histogram(b"AAB");
+/// assert_eq!(counts[b'A' as usize], 2);
+/// assert_eq!(counts[b'B' as usize], 1);
+/// assert_eq!(counts.iter().sum::<usize>(), 3);
 /// ```
-///
-pub fn total_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
-    entropy::<F>(data) * (F::from_usize(data.len()).unwrap())
+pub fn byte_histogram(data: &[u8]) -> [usize; 256] {
+    #[cfg(feature = "simd")]
+    return byte_histogram_interleaved(data);
+    #[cfg(not(feature = "simd"))]
+    {
+        let mut counts = [0usize; 256];
+        for byte in data {
+            counts[*byte as usize] += 1;
+        }
+        counts
+    }
 }
-#[cfg(test)]
-mod test {
-    use super::*;
-    #[test]
-    fn skewed() {
-        let a = String::from("ABAB");
-        let b = String::from("AAAB");
+
+/// Counts the occurrences of each byte value using four interleaved tables.
+///
+/// Consecutive bytes are counted into separate tables that are summed at the
+/// end, so runs of the same byte no longer serialize on a single counter.
+/// With the `simd` feature enabled [`byte_histogram`], and with it [`entropy`]
+/// and the other byte distribution functions, use this implementation.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// An array where index `i` holds the number of times byte `i` occurs
+///
+/// # Example
+///
+/// ```
+/// use shannon::{byte_histogram, byte_histogram_interleaved};
+///
+/// let data = b"Hello, world!";
+/// assert_eq!(byte_histogram_interleaved(data), byte_histogram(data));
+/// ```
+#[cfg(feature = "simd")]
+pub fn byte_histogram_interleaved(data: &[u8]) -> [usize; 256] {
+    let mut lanes = [[0usize; 256]; 4];
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        lanes[0][chunk[0] as usize] += 1;
+        lanes[1][chunk[1] as usize] += 1;
+        lanes[2][chunk[2] as usize] += 1;
+        lanes[3][chunk[3] as usize] += 1;
+    }
+    for byte in chunks.remainder() {
+        lanes[0][*byte as usize] += 1;
+    }
+    let mut counts = lanes[0];
+    for lane in &lanes[1..] {
+        for (count, lane_count) in counts.iter_mut().zip(lane) {
+            *count += lane_count;
+        }
+    }
+    counts
+}
+
+/// Counts the number of distinct byte values in a byte slice.
+///
+/// Text typically uses fewer than 100 distinct bytes while compressed or
+/// encrypted data uses close to all 256, making this a cheap fingerprint.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The number of byte values that occur at least once (0 to 256)
+///
+/// # Example
+///
+/// ```
+/// use shannon::distinct_bytes;
+///
+/// assert_eq!(distinct_bytes(b"Hello, world!"), 10);
+/// assert_eq!(distinct_bytes(b""), 0);
+/// ```
+pub fn distinct_bytes(data: &[u8]) -> usize {
+    byte_histogram(data)
+        .iter()
+        .filter(|&&count| count > 0)
+        .count()
+}
+
+/// Finds the most frequent byte values in a byte slice.
+///
+/// Useful for explaining low entropy, e.g. a block that is mostly zeros.
+/// Bytes with equal counts are ordered by byte value so the output is
+/// deterministic. Bytes that do not occur are never returned.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `n` - The maximum number of byte values to return
+///
+/// # Returns
+///
+/// Up to `n` (byte, count) tuples sorted by descending count
+///
+/// # Example
+///
+/// ```
+/// use shannon::top_bytes;
+///
+/// let top = top_bytes(b"Hello, world!", 2);
+/// assert_eq!(top, vec![(b'l', 3), (b'o', 2)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn top_bytes(data: &[u8], n: usize) -> Vec<(u8, usize)> {
+    let counts = byte_histogram(data);
+    let mut top: Vec<(u8, usize)> = (0..=255u8)
+        .map(|byte| (byte, counts[byte as usize]))
+        .filter(|&(_, count)| count > 0)
+        .collect();
+    top.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    top.truncate(n);
+    top
+}
+
+/// Calculates the Shannon entropy of a byte slice.
+///
+/// Shannon entropy measures the average information content per byte,
+/// ranging from 0 (completely uniform) to 8 (maximum randomness).
+///
+/// The terms of the sum are added in plain floating point, so with `f32` the
+/// result can be off in the last few digits. Use `f64`, or [`entropy_kahan`]
+/// if `f32` is required.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0)
+///
+/// # Example
+///
+/// ## Calculating entropy of a Vec
+///
+/// ```
+/// use shannon::entropy;
+///
+/// let uniform_data = vec![0u8; 100];
+/// let e: f64 = entropy(&uniform_data);
+/// assert_eq!(e, 0.0);
+/// ```
+///
+/// ## Calculating entropy per character of a String
+///
+/// ```
+/// use shannon::entropy;
+///
+/// let text = String::from("AABB");
+/// let e: f64 = entropy(text.as_bytes());
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    entropy_with_counts(data).0
+}
+
+/// Calculates the Shannon entropy of a byte histogram.
+///
+/// Takes the count of each byte value, as returned by [`byte_histogram`],
+/// instead of the data itself. Histograms of separate pieces of data can be
+/// summed before calling this to get the entropy of the whole.
+///
+/// # Arguments
+///
+/// * `counts` - The number of occurrences of each byte value
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), 0.0 if every count is 0
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_from_counts;
+///
+/// let mut counts = [0; 256];
+/// counts[b'A' as usize] = 2;
+/// counts[b'B' as usize] = 2;
+/// let e: f64 = entropy_from_counts(&counts);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy_from_counts<F: Float + FromPrimitive>(counts: &[usize; 256]) -> F {
+    histogram_entropy(counts, counts.iter().sum())
+}
+
+/// Chunk size in which `entropy_with_counts` compares its leading run.
+const RUN_CHUNK: usize = 4096;
+
+/// Calculates the Shannon entropy of a byte slice along with its histogram.
+///
+/// The byte counts used to calculate the entropy are returned with it, e.g.
+/// for charting both, so the data only needs to be counted once.
+///
+/// Before counting, the data is compared with its first byte, since a run of
+/// a single repeated byte (such as zero padding) is much faster to recognise
+/// than to count. Only the bytes after the leading run are counted, so at
+/// most one chunk of 4 KiB is looked at twice.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0) and the count of each
+/// byte value, as returned by [`byte_histogram`]
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_with_counts;
+///
+/// let (e, counts) = entropy_with_counts::<f64>(b"AABB");
+/// assert_eq!(e, 1.0);
+/// assert_eq!(counts[b'A' as usize], 2);
+/// ```
+pub fn entropy_with_counts<F: Float + FromPrimitive>(data: &[u8]) -> (F, [usize; 256]) {
+    // Counting a single repeated byte is slow, as every increment hits the
+    // same counter. The histogram level check in `try_histogram_entropy` only
+    // avoids the logarithms afterwards, not the counting.
+    let Some(&first) = data.first() else {
+        return (F::zero(), [0; 256]);
+    };
+    let run: usize = data
+        .chunks(RUN_CHUNK)
+        .take_while(|chunk| chunk.iter().all(|&byte| byte == first))
+        .map(<[u8]>::len)
+        .sum();
+    let mut counts = byte_histogram(&data[run..]);
+    counts[first as usize] += run;
+    if run == data.len() {
+        return (F::zero(), counts);
+    }
+    (entropy_from_counts(&counts), counts)
+}
+
+/// Calculates the Shannon entropy of a byte slice using an arbitrary
+/// logarithm base.
+///
+/// A base of 2 gives bits (identical to [`entropy`]), `e` gives nats and 10
+/// gives dits.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `base` - The logarithm base, must be greater than 1
+///
+/// # Returns
+///
+/// The entropy value per byte in units of `base`, or NaN if `base` is not
+/// greater than 1.
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_base;
+///
+/// let text = String::from("AABB");
+/// let e: f64 = entropy_base(text.as_bytes(), std::f64::consts::E);
+/// assert!((e - std::f64::consts::LN_2).abs() < 1e-12);
+/// assert!(entropy_base::<f64>(text.as_bytes(), 1.0).is_nan());
+/// ```
+pub fn entropy_base<F: Float + FromPrimitive>(data: &[u8], base: F) -> F {
+    if base.is_nan() || base <= F::one() {
+        return F::nan();
+    }
+    entropy::<F>(data) / base.log2()
+}
+
+/// Calculates the entropy in bits per byte of a count table totalling `len`.
+fn histogram_entropy<F: Float + FromPrimitive>(counts: &[usize; 256], len: usize) -> F {
+    try_histogram_entropy(counts, len).unwrap()
+}
+
+/// An integer type used for byte counts, `usize` for slices and `u64` for an
+/// [`EntropyAccumulator`].
+trait Count: Copy + PartialOrd + ToPrimitive + Zero + One {}
+
+impl<C: Copy + PartialOrd + ToPrimitive + Zero + One> Count for C {}
+
+/// Fallible version of [`histogram_entropy`], for any type of count.
+fn try_histogram_entropy<F: Float + FromPrimitive, C: Count>(
+    counts: &[C; 256],
+    len: C,
+) -> Result<F, EntropyError> {
+    let convert =
+        |n: C| F::from(n).ok_or(EntropyError::Conversion(n.to_usize().unwrap_or(usize::MAX)));
+    let data_len = convert(len)?;
+    // A single symbol has no entropy, and n distinct symbols have log2(n).
+    if counts.contains(&len) {
+        return Ok(F::zero());
+    }
+    if len.to_usize().is_some_and(|len| len <= counts.len())
+        && counts.iter().all(|&count| count <= C::one())
+    {
+        return Ok(data_len.log2());
+    }
+    let mut entropy = F::zero();
+    for &count in counts {
+        if count.is_zero() {
+            continue;
+        }
+        let p_x = convert(count)? / data_len;
+        entropy = entropy - p_x * p_x.log2();
+    }
+    Ok(entropy)
+}
+
+/// Errors returned by the fallible entropy functions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropyError {
+    /// A length or count could not be represented in the float type
+    Conversion(usize),
+}
+
+impl fmt::Display for EntropyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EntropyError::Conversion(value) => {
+                write!(f, "could not convert {value} to the float type")
+            }
+        }
+    }
+}
+
+impl core::error::Error for EntropyError {}
+
+/// Calculates the Shannon entropy of a byte slice without panicking.
+///
+/// Identical to [`entropy`], except a length or count that cannot be
+/// converted to `F` is reported as an error instead of panicking.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), or an [`EntropyError`]
+///
+/// # Example
+///
+/// ```
+/// use shannon::try_entropy;
+///
+/// let text = String::from("AABB");
+/// let e: f64 = try_entropy(text.as_bytes()).unwrap();
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn try_entropy<F: Float + FromPrimitive>(data: &[u8]) -> Result<F, EntropyError> {
+    try_histogram_entropy(&byte_histogram(data), data.len())
+}
+/// Calculates the total Shannon entropy of a byte slice.
+///
+/// Shannon entropy measures the average information content per byte,
+/// multiplied by the length this returns the total entropy of the byte slice.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The total entropy of data
+///
+/// # Example
+///
+/// ## Calculating total entropy of a String
+///
+/// ```
+/// use shannon::total_entropy;
+///
+/// let text = String::from("AABB");
+/// let e = total_entropy::<f64>(text.as_bytes());
+/// assert_eq!(e, 4.0);
+/// ```
+///
+pub fn total_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    entropy::<F>(data) * (F::from_usize(data.len()).unwrap())
+}
+
+/// Estimates the compressed size of a byte slice from its entropy.
+///
+/// This is [`total_entropy`] expressed in bytes, the theoretical floor for a
+/// compressor that codes each byte independently using the byte frequencies of
+/// the data. It is not a prediction of what zlib or any real compressor will
+/// achieve: those exploit repetition between bytes and so can beat it on
+/// structured data, and they carry header and table overhead so do worse on
+/// small or random inputs.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The estimated compressed size in bytes
+///
+/// # Example
+///
+/// ```
+/// use shannon::estimated_compressed_size;
+///
+/// let text = String::from("AABB");
+/// let size: f64 = estimated_compressed_size(text.as_bytes());
+/// assert_eq!(size, 0.5);
+/// ```
+pub fn estimated_compressed_size<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    total_entropy::<F>(data) / F::from_f64(8.0).unwrap()
+}
+
+/// Estimates the compression ratio of a byte slice from its entropy.
+///
+/// This is [`estimated_compressed_size`] divided by the length of the data,
+/// which is the same as the entropy per byte divided by 8. Like that estimate
+/// it is an entropy-based floor, not the ratio a real compressor will reach.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The estimated compressed size as a fraction of the original (0.0 to 1.0),
+/// 0.0 for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::compression_ratio;
+///
+/// let text = String::from("AABB");
+/// let ratio: f64 = compression_ratio(text.as_bytes());
+/// assert_eq!(ratio, 0.125);
+/// ```
+pub fn compression_ratio<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    estimated_compressed_size::<F>(data) / F::from_usize(data.len()).unwrap()
+}
+/// Calculates the Shannon entropy of a slice of arbitrary symbols.
+///
+/// Unlike [`entropy`], which is specialised for bytes, this counts symbols in
+/// a `HashMap` so it works for any alphabet such as `u16` tokens or word IDs.
+///
+/// # Arguments
+///
+/// * `data` - A slice of symbols to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per symbol (0.0 to log2 of the number of
+/// distinct symbols)
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_generic;
+///
+/// let tokens = vec![1000u16, 2000, 3000, 4000];
+/// let e: f64 = entropy_generic(&tokens);
+/// assert_eq!(e, 2.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn entropy_generic<T: Eq + Hash, F: Float + FromPrimitive>(data: &[T]) -> F {
+    let data_len = F::from_usize(data.len()).unwrap();
+    let mut counts: HashMap<&T, usize> = HashMap::new();
+    for symbol in data {
+        *counts.entry(symbol).or_insert(0) += 1;
+    }
+    let mut entropy = F::zero();
+    for &count in counts.values() {
+        let p_x = F::from_usize(count).unwrap() / data_len;
+        entropy = entropy - p_x * p_x.log2();
+    }
+    entropy
+}
+
+/// Calculates the Shannon entropy of a string over its Unicode scalar values.
+///
+/// Unlike [`entropy`] on the UTF-8 bytes, each multibyte character counts as
+/// a single symbol, so the result is meaningful for non-ASCII text.
+///
+/// # Arguments
+///
+/// * `text` - A string to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per character (0.0 to log2 of the number of
+/// distinct characters)
+///
+/// # Example
+///
+/// ```
+/// use shannon::char_entropy;
+///
+/// let e: f64 = char_entropy("ééßß");
+/// assert_eq!(e, 1.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn char_entropy<F: Float + FromPrimitive>(text: &str) -> F {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    let mut len = 0;
+    for c in text.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+        len += 1;
+    }
+    let data_len = F::from_usize(len).unwrap();
+    let mut entropy = F::zero();
+    for &count in counts.values() {
+        let p_x = F::from_usize(count).unwrap() / data_len;
+        entropy = entropy - p_x * p_x.log2();
+    }
+    entropy
+}
+
+/// Calculates the Shannon entropy of a string over its words.
+///
+/// Words are the tokens left by splitting on Unicode whitespace, as by
+/// [`str::split_whitespace`]. Tokens are compared exactly, so words differing
+/// in case or attached punctuation (`"The"`, `"the"`, `"the,"`) are distinct.
+///
+/// # Arguments
+///
+/// * `text` - A string to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per word (0.0 to log2 of the number of distinct
+/// words)
+///
+/// # Example
+///
+/// ```
+/// use shannon::word_entropy;
+///
+/// let e: f64 = word_entropy("the cat the dog");
+/// assert_eq!(e, 1.5);
+/// ```
+#[cfg(feature = "std")]
+pub fn word_entropy<F: Float + FromPrimitive>(text: &str) -> F {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    entropy_generic(&words)
+}
+
+/// Summary of several entropy metrics for a byte slice.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntropyReport<F> {
+    /// Entropy in bits per byte (0.0 to 8.0)
+    pub per_byte_entropy: F,
+    /// Entropy per byte multiplied by the length
+    pub total_entropy: F,
+    /// Number of bytes analyzed
+    pub length: usize,
+    /// Number of distinct byte values present
+    pub distinct_bytes: usize,
+}
+
+/// Calculates several entropy metrics of a byte slice in a single pass.
+///
+/// This is equivalent to calling [`entropy`] and [`total_entropy`]
+/// separately, but only counts the bytes once.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// An [`EntropyReport`] describing the data
+///
+/// # Example
+///
+/// ```
+/// use shannon::analyze;
+///
+/// let text = String::from("AABB");
+/// let report = analyze::<f64>(text.as_bytes());
+/// assert_eq!(report.per_byte_entropy, 1.0);
+/// assert_eq!(report.total_entropy, 4.0);
+/// assert_eq!(report.length, 4);
+/// assert_eq!(report.distinct_bytes, 2);
+/// ```
+pub fn analyze<F: Float + FromPrimitive>(data: &[u8]) -> EntropyReport<F> {
+    let counts = byte_histogram(data);
+    let per_byte_entropy = histogram_entropy::<F>(&counts, data.len());
+    EntropyReport {
+        per_byte_entropy,
+        total_entropy: per_byte_entropy * F::from_usize(data.len()).unwrap(),
+        length: data.len(),
+        distinct_bytes: counts.iter().filter(|&&count| count > 0).count(),
+    }
+}
+
+/// Calculates the normalized Shannon entropy of a byte slice.
+///
+/// This is the entropy in bits per byte divided by 8, the maximum possible
+/// entropy for a byte alphabet of 256 symbols. The result is on the same
+/// scale as the thresholds passed to [`detect_edges`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The normalized entropy value (0.0 to 1.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::normalized_entropy;
+///
+/// let text = String::from("AABB");
+/// let e: f64 = normalized_entropy(text.as_bytes());
+/// assert_eq!(e, 0.125);
+/// ```
+pub fn normalized_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    let eight = F::from_f64(8.0).unwrap();
+    (entropy::<F>(data) / eight).max(F::zero()).min(F::one())
+}
+
+/// Calculates the Shannon redundancy of a byte slice.
+///
+/// Redundancy is `1 - H / H_max`, the fraction of the data that could in
+/// principle be removed by an ideal order-0 coder. `H_max` is taken to be
+/// 8 bits, i.e. the alphabet is assumed to be all 256 byte values; use
+/// [`normalized_entropy_for_alphabet`] for smaller alphabets.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The redundancy (0.0 to 1.0), the complement of [`normalized_entropy`]
+///
+/// # Example
+///
+/// ```
+/// use shannon::redundancy;
+///
+/// let e: f64 = redundancy(b"AABB");
+/// assert_eq!(e, 0.875);
+/// ```
+pub fn redundancy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    let eight = F::from_f64(8.0).unwrap();
+    (F::one() - entropy::<F>(data) / eight)
+        .max(F::zero())
+        .min(F::one())
+}
+
+/// Calculates the Shannon entropy of a byte slice as a percentage of the
+/// maximum.
+///
+/// The maximum is taken to be 8 bits per byte, which is only reached when all
+/// 256 byte values are equally likely, so e.g. ASCII text can never reach
+/// 100%.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy as a percentage (0.0 to 100.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_percent;
+///
+/// let e: f64 = entropy_percent(b"AABB");
+/// assert_eq!(e, 12.5);
+/// ```
+pub fn entropy_percent<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    percent_of_max_entropy(entropy::<F>(data))
+}
+
+/// Converts an entropy in bits per byte to a percentage of the 8 bit maximum.
+///
+/// This is the conversion used by [`entropy_percent`], for entropies that
+/// were calculated some other way, e.g. by an [`EntropyAccumulator`].
+///
+/// # Arguments
+///
+/// * `bits` - An entropy in bits per byte (0.0 to 8.0)
+///
+/// # Returns
+///
+/// The entropy as a percentage (0.0 to 100.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::percent_of_max_entropy;
+///
+/// assert_eq!(percent_of_max_entropy(6.0_f64), 75.0);
+/// ```
+pub fn percent_of_max_entropy<F: Float + FromPrimitive>(bits: F) -> F {
+    bits / F::from_f64(8.0).unwrap() * F::from_f64(100.0).unwrap()
+}
+
+/// Calculates the Shannon entropy of a byte slice normalized for an alphabet size.
+///
+/// This is the entropy in bits per symbol divided by `log2(alphabet_size)`,
+/// the maximum possible entropy for that alphabet, so restricted alphabets
+/// such as DNA (4 symbols) or hex digits (16) also reach 1.0 when every
+/// symbol is equally likely. Passing 256 gives the same result as
+/// [`normalized_entropy`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `alphabet_size` - The number of symbols the data is drawn from
+///
+/// # Returns
+///
+/// The normalized entropy value (0.0 to 1.0), 0.0 if `alphabet_size` is 0
+/// or 1. Values above 1.0 mean the data uses more than `alphabet_size`
+/// distinct bytes.
+///
+/// # Example
+///
+/// ```
+/// use shannon::normalized_entropy_for_alphabet;
+///
+/// let dna = String::from("ACGTACGT");
+/// let e: f64 = normalized_entropy_for_alphabet(dna.as_bytes(), 4);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn normalized_entropy_for_alphabet<F: Float + FromPrimitive>(
+    data: &[u8],
+    alphabet_size: usize,
+) -> F {
+    if alphabet_size <= 1 {
+        return F::zero();
+    }
+    let max_entropy = F::from_usize(alphabet_size).unwrap().log2();
+    (entropy::<F>(data) / max_entropy).max(F::zero())
+}
+/// Calculates the Rényi entropy of order `alpha` of a byte slice.
+///
+/// Rényi entropy generalises Shannon entropy: order 0 is the Hartley entropy
+/// (log2 of the number of distinct bytes), order 1 is the Shannon entropy,
+/// order 2 is the collision entropy and order infinity is the min-entropy.
+/// Order 1 is a singularity of the formula so [`entropy`] is used instead.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `alpha` - The order of the entropy, must be non-negative
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), or NaN if `alpha` is
+/// negative or NaN.
+///
+/// # Example
+///
+/// ```
+/// use shannon::{entropy, renyi_entropy};
+///
+/// let text = String::from("AAAB");
+/// let h1: f64 = renyi_entropy(text.as_bytes(), 1.0);
+/// let h2: f64 = renyi_entropy(text.as_bytes(), 2.0);
+/// assert_eq!(h1, entropy::<f64>(text.as_bytes()));
+/// assert!(h2 < h1);
+/// ```
+pub fn renyi_entropy<F: Float + FromPrimitive>(data: &[u8], alpha: F) -> F {
+    if alpha.is_nan() || alpha < F::zero() {
+        return F::nan();
+    }
+    if alpha == F::one() {
+        return entropy(data);
+    }
+    if alpha.is_infinite() {
+        return min_entropy(data);
+    }
+    if data.is_empty() {
+        return F::zero();
+    }
+    let data_len = F::from_usize(data.len()).unwrap();
+    let counts = byte_histogram(data);
+    let max_count = counts.iter().copied().max().unwrap();
+    let p_max = F::from_usize(max_count).unwrap() / data_len;
+    // Factor out p_max so that large orders don't underflow to zero.
+    let mut sum = F::zero();
+    for count in counts {
+        if count == 0 {
+            continue;
+        }
+        let ratio = F::from_usize(count).unwrap() / F::from_usize(max_count).unwrap();
+        sum = sum + ratio.powf(alpha);
+    }
+    (alpha * p_max.log2() + sum.log2()) / (F::one() - alpha)
+}
+
+/// Calculates the min-entropy of a byte slice.
+///
+/// Min-entropy is `-log2(max p)` where `max p` is the probability of the most
+/// frequent byte. It measures worst-case unpredictability and is never
+/// greater than the Shannon entropy of the same data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The min-entropy value in bits per byte (0.0 to 8.0), 0.0 for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::min_entropy;
+///
+/// let text = String::from("AAAB");
+/// let e: f64 = min_entropy(text.as_bytes());
+/// assert_eq!(e, -(0.75_f64).log2());
+/// ```
+pub fn min_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    let counts = byte_histogram(data);
+    let max_count = counts.iter().copied().max().unwrap();
+    let p_max = F::from_usize(max_count).unwrap() / F::from_usize(data.len()).unwrap();
+    F::zero() - p_max.log2()
+}
+
+/// Calculates the guessing entropy of a byte slice.
+///
+/// Guessing entropy is the expected number of guesses needed to find a byte
+/// drawn from the data when guessing the most frequent bytes first, i.e.
+/// `Σ i * p_(i)` with probabilities sorted in descending order and ranks `i`
+/// starting at 1. Unlike [`min_entropy`] it is measured in guesses, not bits.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The expected number of guesses (1.0 to 128.5), 0.0 for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::guessing_entropy;
+///
+/// let text = String::from("AAAB");
+/// let guesses: f64 = guessing_entropy(text.as_bytes());
+/// assert_eq!(guesses, 1.0 * 0.75 + 2.0 * 0.25);
+/// ```
+pub fn guessing_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    let mut counts = byte_histogram(data);
+    counts.sort_unstable_by(|a, b| b.cmp(a));
+    let data_len = F::from_usize(data.len()).unwrap();
+    let mut sum = F::zero();
+    for (rank, &count) in counts.iter().take_while(|&&count| count > 0).enumerate() {
+        let p = F::from_usize(count).unwrap() / data_len;
+        sum = sum + F::from_usize(rank + 1).unwrap() * p;
+    }
+    sum
+}
+
+/// Calculates the Tsallis entropy of order `q` of a byte slice.
+///
+/// Tsallis entropy is `(1 - Σ p^q) / (q - 1)`, a non-extensive generalisation
+/// of Shannon entropy measured in natural units. Order 1 is a singularity of
+/// the formula, where it converges to the Shannon entropy in nats, so
+/// [`entropy`] scaled by `ln(2)` is returned instead.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `q` - The entropic index
+///
+/// # Returns
+///
+/// The Tsallis entropy of the byte distribution, 0.0 for empty data, or NaN
+/// if `q` is NaN.
+///
+/// # Example
+///
+/// ```
+/// use shannon::tsallis_entropy;
+///
+/// let text = String::from("AAAB");
+/// let s2: f64 = tsallis_entropy(text.as_bytes(), 2.0);
+/// assert!((s2 - (1.0 - 0.75 * 0.75 - 0.25 * 0.25)).abs() < 1e-12);
+/// ```
+pub fn tsallis_entropy<F: Float + FromPrimitive>(data: &[u8], q: F) -> F {
+    if q.is_nan() {
+        return F::nan();
+    }
+    if q == F::one() {
+        return entropy::<F>(data) * F::from_f64(core::f64::consts::LN_2).unwrap();
+    }
+    if data.is_empty() {
+        return F::zero();
+    }
+    let data_len = F::from_usize(data.len()).unwrap();
+    let mut sum = F::zero();
+    for count in byte_histogram(data) {
+        if count == 0 {
+            continue;
+        }
+        let p = F::from_usize(count).unwrap() / data_len;
+        sum = sum + p.powf(q);
+    }
+    (F::one() - sum) / (q - F::one())
+}
+
+/// Calculates the Hartley entropy of a byte slice.
+///
+/// Hartley entropy is `log2` of the number of distinct bytes, the Rényi
+/// entropy of order 0. It is an upper bound for the Shannon [`entropy`] of the
+/// same data, reached when every distinct byte is equally frequent.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), 0.0 for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::hartley_entropy;
+///
+/// let text = String::from("AAAB");
+/// let e: f64 = hartley_entropy(text.as_bytes());
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn hartley_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    let distinct = distinct_bytes(data);
+    if distinct <= 1 {
+        return F::zero();
+    }
+    F::from_usize(distinct).unwrap().log2()
+}
+
+/// Calculates the chi-square statistic of a byte slice against a uniform distribution.
+///
+/// Each byte value is expected `len / 256` times in uniformly random data; the
+/// statistic sums `(count - expected)^2 / expected` over all 256 values. It
+/// complements [`entropy`], which can be close to 8 for structured data whose
+/// histogram happens to be flat, e.g. a repeating counter. For random data the
+/// statistic follows a chi-square distribution with 255 degrees of freedom, so
+/// values near 255 are expected. Values far below that mean the histogram is
+/// too even to be random, values far above mean some bytes are over or
+/// under-represented.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The chi-square statistic, 0.0 for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::chi_square_uniform;
+///
+/// let counter: Vec<u8> = (0..=255).collect();
+/// let flat: f64 = chi_square_uniform(&counter);
+/// assert_eq!(flat, 0.0);
+///
+/// let skewed: f64 = chi_square_uniform(&[0u8; 256]);
+/// assert_eq!(skewed, 65280.0);
+/// ```
+pub fn chi_square_uniform<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    let expected = F::from_usize(data.len()).unwrap() / F::from_usize(256).unwrap();
+    let mut sum = F::zero();
+    for count in byte_histogram(data) {
+        let diff = F::from_usize(count).unwrap() - expected;
+        sum = sum + diff * diff / expected;
+    }
+    sum
+}
+
+/// Tests whether a byte slice is consistent with uniformly random bytes.
+///
+/// Computes the p-value of [`chi_square_uniform`] under the chi-square
+/// distribution with 255 degrees of freedom, i.e. the probability that truly
+/// uniform random data of the same length gives a statistic at least as
+/// large. A low p-value (e.g. below 0.01) flags non-random data. As with any
+/// chi-square test, the result is only reliable when each byte value is
+/// expected several times, i.e. for at least a few KiB of data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The p-value (0.0 to 1.0), 1.0 for an empty slice
+///
+/// # Example
+///
+/// ```
+/// use shannon::uniformity_pvalue;
+///
+/// let text = "The quick brown fox jumps over the lazy dog. ".repeat(100);
+/// let p: f64 = uniformity_pvalue(text.as_bytes());
+/// assert!(p < 1e-9);
+/// ```
+pub fn uniformity_pvalue<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    let half = F::from_f64(0.5).unwrap();
+    let degrees_of_freedom = F::from_usize(255).unwrap();
+    gamma_q(
+        degrees_of_freedom * half,
+        chi_square_uniform::<F>(data) * half,
+    )
+}
+
+/// Natural logarithm of the gamma function for `x > 0`, by the Lanczos
+/// approximation.
+fn ln_gamma<F: Float + FromPrimitive>(x: F) -> F {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - F::one();
+    let mut sum = F::from_f64(COEFFICIENTS[0]).unwrap();
+    for (i, &c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum = sum + F::from_f64(c).unwrap() / (x + F::from_usize(i).unwrap());
+    }
+    let t = x + F::from_f64(7.5).unwrap();
+    let half_ln_two_pi = F::from_f64(0.918_938_533_204_672_7).unwrap();
+    half_ln_two_pi + (x + F::from_f64(0.5).unwrap()) * t.ln() - t + sum.ln()
+}
+
+/// Regularized upper incomplete gamma function `Q(a, x)`, by its series for
+/// `x < a + 1` and its continued fraction otherwise.
+fn gamma_q<F: Float + FromPrimitive>(a: F, x: F) -> F {
+    const MAX_ITERATIONS: usize = 1000;
+    if x <= F::zero() {
+        return F::one();
+    }
+    let scale = (x.ln() * a - x - ln_gamma(a)).exp();
+    if x < a + F::one() {
+        let mut term = F::one() / a;
+        let mut sum = term;
+        let mut n = a;
+        for _ in 0..MAX_ITERATIONS {
+            n = n + F::one();
+            term = term * x / n;
+            sum = sum + term;
+            if term.abs() < sum.abs() * F::epsilon() {
+                break;
+            }
+        }
+        return (F::one() - sum * scale).max(F::zero());
+    }
+    // Modified Lentz's method
+    let tiny = F::min_positive_value() / F::epsilon();
+    let two = F::from_f64(2.0).unwrap();
+    let mut b = x + F::one() - a;
+    let mut c = F::one() / tiny;
+    let mut d = F::one() / b;
+    let mut h = d;
+    for i in 1..MAX_ITERATIONS {
+        let i = F::from_usize(i).unwrap();
+        let an = -i * (i - a);
+        b = b + two;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = F::one() / d;
+        let delta = d * c;
+        h = h * delta;
+        if (delta - F::one()).abs() < F::epsilon() {
+            break;
+        }
+    }
+    (scale * h).min(F::one())
+}
+
+/// Calculates the Shannon entropy of a sliding window over a byte slice.
+///
+/// The window of `window` bytes is advanced by `step` bytes at a time, and
+/// only full windows are considered: any trailing bytes that do not fill a
+/// complete window are dropped.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `window` - Size of each window in bytes
+/// * `step` - Number of bytes to advance between windows
+///
+/// # Returns
+///
+/// A vector of (offset, entropy) tuples, where offset is the starting byte of
+/// each window. Empty if `window` is larger than `data`.
+///
+/// # Panics
+///
+/// Panics if `window` or `step` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::windowed_entropy;
+///
+/// let data = b"AAAABBBB";
+/// let values = windowed_entropy::<f64>(data, 4, 2);
+/// assert_eq!(values, vec![(0, 0.0), (2, 1.0), (4, 0.0)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn windowed_entropy<F: Float + FromPrimitive>(
+    data: &[u8],
+    window: usize,
+    step: usize,
+) -> Vec<(usize, F)> {
+    window_entropies(data, window, step).collect()
+}
+
+/// Calls a function with the Shannon entropy of each sliding window over a
+/// byte slice.
+///
+/// Visits the same windows as [`windowed_entropy`], in order, but passes each
+/// (offset, entropy) pair to `f` as it is calculated instead of collecting a
+/// vector, e.g. to update a plot while scanning.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `window` - Size of each window in bytes
+/// * `step` - Number of bytes to advance between windows
+/// * `f` - Called with the starting byte and entropy of each window
+///
+/// # Panics
+///
+/// Panics if `window` or `step` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::for_each_window;
+///
+/// let mut values = Vec::new();
+/// for_each_window(b"AAAABBBB", 4, 2, |offset, e: f64| values.push((offset, e)));
+/// assert_eq!(values, vec![(0, 0.0), (2, 1.0), (4, 0.0)]);
+/// ```
+pub fn for_each_window<F, G>(data: &[u8], window: usize, step: usize, mut f: G)
+where
+    F: Float + FromPrimitive,
+    G: FnMut(usize, F),
+{
+    window_entropies(data, window, step).for_each(|(offset, e)| f(offset, e));
+}
+
+/// The full windows of `data` visited by [`windowed_entropy`], with their
+/// offsets and entropies.
+fn window_entropies<F: Float + FromPrimitive>(
+    data: &[u8],
+    window: usize,
+    step: usize,
+) -> impl Iterator<Item = (usize, F)> + '_ {
+    data.windows(window)
+        .enumerate()
+        .step_by(step)
+        .map(|(offset, w)| (offset, entropy::<F>(w)))
+}
+
+/// Accumulates byte counts across multiple chunks of data.
+///
+/// Useful for calculating the Shannon entropy of data that does not fit in
+/// memory. Feeding chunks to [`EntropyAccumulator::update`] and then calling
+/// [`EntropyAccumulator::finalize`] gives the same result as calling
+/// [`entropy`] on the concatenation of all chunks.
+///
+/// # Example
+///
+/// ```
+/// use shannon::{entropy, EntropyAccumulator};
+///
+/// let mut acc = EntropyAccumulator::new();
+/// acc.update(b"AA");
+/// acc.update(b"BB");
+/// let e: f64 = acc.finalize();
+/// assert_eq!(e, entropy::<f64>(b"AABB"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntropyAccumulator {
+    counts: [u64; 256],
+    len: u64,
+}
+
+impl Default for EntropyAccumulator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AddAssign<&EntropyAccumulator> for EntropyAccumulator {
+    fn add_assign(&mut self, other: &EntropyAccumulator) {
+        self.merge(other);
+    }
+}
+
+impl Add for EntropyAccumulator {
+    type Output = EntropyAccumulator;
+
+    fn add(mut self, other: EntropyAccumulator) -> EntropyAccumulator {
+        self.merge(&other);
+        self
+    }
+}
+
+impl EntropyAccumulator {
+    /// Creates an empty accumulator.
+    pub fn new() -> Self {
+        Self {
+            counts: [0; 256],
+            len: 0,
+        }
+    }
+
+    /// Adds the bytes of `data` to the count table.
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.counts[*byte as usize] += 1;
+        }
+        self.len += data.len() as u64;
+    }
+
+    /// Removes the bytes of `data`, previously added with [`update`], from
+    /// the count table.
+    ///
+    /// Adding the bytes entering a window and removing those leaving it gives
+    /// the exact entropy of a sliding window without recounting it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `data` contains more of a byte value than has been added,
+    /// leaving the accumulator unchanged.
+    ///
+    /// [`update`]: EntropyAccumulator::update
+    pub fn remove(&mut self, data: &[u8]) {
+        let mut removed = [0u64; 256];
+        for byte in data {
+            removed[*byte as usize] += 1;
+        }
+        for (byte, (count, removed)) in self.counts.iter().zip(&removed).enumerate() {
+            assert!(
+                removed <= count,
+                "removed byte {byte:#04x} that was never added"
+            );
+        }
+        for (count, removed) in self.counts.iter_mut().zip(&removed) {
+            *count -= removed;
+        }
+        self.len -= data.len() as u64;
+    }
+
+    /// Adds the counts of `other` to this accumulator.
+    ///
+    /// Merging accumulators that each saw part of the data gives the same
+    /// result as one accumulator that saw all of it, in any order, so chunks
+    /// can be counted on separate threads and merged before [`finalize`].
+    ///
+    /// [`finalize`]: EntropyAccumulator::finalize
+    pub fn merge(&mut self, other: &EntropyAccumulator) {
+        for (count, other_count) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other_count;
+        }
+        self.len += other.len;
+    }
+
+    /// Returns the count of each byte value seen so far.
+    pub fn counts(&self) -> &[u64; 256] {
+        &self.counts
+    }
+
+    /// Returns the total number of bytes seen so far.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if no bytes have been seen.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Calculates the Shannon entropy of all bytes seen so far.
+    ///
+    /// # Returns
+    ///
+    /// The entropy value in bits per byte (0.0 to 8.0)
+    pub fn finalize<F: Float + FromPrimitive>(&self) -> F {
+        try_histogram_entropy(&self.counts, self.len).unwrap()
+    }
+}
+
+/// Calculates the Kullback-Leibler divergence of `observed` from `reference`.
+///
+/// This is `Σ p(x) log2(p(x) / q(x))` over the bytes present in `observed`,
+/// where `p` and `q` are the byte distributions of `observed` and
+/// `reference`. If a byte occurs in `observed` but never in `reference` the
+/// divergence is infinite; use [`kl_divergence_smoothed`] to avoid this.
+///
+/// # Arguments
+///
+/// * `observed` - A byte slice giving the observed distribution
+/// * `reference` - A byte slice giving the reference distribution
+///
+/// # Returns
+///
+/// The divergence in bits, 0.0 when the distributions are identical
+///
+/// # Example
+///
+/// ```
+/// use shannon::kl_divergence;
+///
+/// let d: f64 = kl_divergence(b"AABB", b"ABAB");
+/// assert_eq!(d, 0.0);
+/// let d: f64 = kl_divergence(b"AB", b"AAAA");
+/// assert!(d.is_infinite());
+/// ```
+pub fn kl_divergence<F: Float + FromPrimitive>(observed: &[u8], reference: &[u8]) -> F {
+    kl_divergence_smoothed(observed, reference, F::zero())
+}
+
+/// Calculates the Kullback-Leibler divergence of `observed` from `reference`
+/// with additive smoothing of the reference distribution.
+///
+/// `epsilon` pseudo-counts are added to each of the 256 reference byte
+/// counts, so any positive `epsilon` gives a finite result even when
+/// `reference` lacks bytes that occur in `observed`. An `epsilon` of 0 is
+/// identical to [`kl_divergence`].
+///
+/// # Arguments
+///
+/// * `observed` - A byte slice giving the observed distribution
+/// * `reference` - A byte slice giving the reference distribution
+/// * `epsilon` - Non-negative pseudo-count added to every reference byte
+///
+/// # Returns
+///
+/// The divergence in bits
+///
+/// # Example
+///
+/// ```
+/// use shannon::kl_divergence_smoothed;
+///
+/// let d: f64 = kl_divergence_smoothed(b"AB", b"AAAA", 0.5);
+/// assert!(d.is_finite() && d > 0.0);
+/// ```
+pub fn kl_divergence_smoothed<F: Float + FromPrimitive>(
+    observed: &[u8],
+    reference: &[u8],
+    epsilon: F,
+) -> F {
+    let p_counts = byte_histogram(observed);
+    let q_counts = byte_histogram(reference);
+    let p_len = F::from_usize(observed.len()).unwrap();
+    let q_len = F::from_usize(reference.len()).unwrap() + epsilon * F::from_f64(256.0).unwrap();
+    let mut divergence = F::zero();
+    for (&p_count, &q_count) in p_counts.iter().zip(q_counts.iter()) {
+        if p_count == 0 {
+            continue;
+        }
+        let q_x = F::from_usize(q_count).unwrap() + epsilon;
+        if q_x == F::zero() {
+            return F::infinity();
+        }
+        let p_x = F::from_usize(p_count).unwrap() / p_len;
+        divergence = divergence + p_x * (p_x / (q_x / q_len)).log2();
+    }
+    divergence
+}
+
+/// Calculates the cross-entropy of `data` with respect to `model`.
+///
+/// This is `-Σ p(x) log2(q(x))` where `p` is the byte distribution of `data`
+/// and `q` the byte distribution of `model`. It equals [`entropy`] when both
+/// slices have the same distribution. If a byte occurs in `data` but never in
+/// `model` the cross-entropy is infinite.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice giving the observed distribution
+/// * `model` - A byte slice giving the model distribution
+///
+/// # Returns
+///
+/// The cross-entropy in bits per byte
+///
+/// # Example
+///
+/// ```
+/// use shannon::cross_entropy;
+///
+/// let h: f64 = cross_entropy(b"AABB", b"ABCD");
+/// assert_eq!(h, 2.0);
+/// ```
+pub fn cross_entropy<F: Float + FromPrimitive>(data: &[u8], model: &[u8]) -> F {
+    let p_counts = byte_histogram(data);
+    let q_counts = byte_histogram(model);
+    let p_len = F::from_usize(data.len()).unwrap();
+    let q_len = F::from_usize(model.len()).unwrap();
+    let mut cross = F::zero();
+    for (&p_count, &q_count) in p_counts.iter().zip(q_counts.iter()) {
+        if p_count == 0 {
+            continue;
+        }
+        if q_count == 0 {
+            return F::infinity();
+        }
+        let p_x = F::from_usize(p_count).unwrap() / p_len;
+        let q_x = F::from_usize(q_count).unwrap() / q_len;
+        cross = cross - p_x * q_x.log2();
+    }
+    cross
+}
+
+/// Calculates the conditional entropy of each byte given the previous byte.
+///
+/// This is `H(next | prev) = H(prev, next) - H(prev)` taken over every pair
+/// of consecutive bytes. Data where each byte is predictable from the one
+/// before it, such as a repeating pattern, gives a value near 0.0, while
+/// random data gives a value close to its [`entropy`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The conditional entropy in bits per byte (0.0 to 8.0), 0.0 for slices
+/// shorter than 2 bytes
+///
+/// # Example
+///
+/// ```
+/// use shannon::conditional_entropy;
+///
+/// let h: f64 = conditional_entropy(b"ABCABCABCABC");
+/// assert_eq!(h, 0.0);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn conditional_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.len() < 2 {
+        return F::zero();
+    }
+    let pairs = data.len() - 1;
+    let joint_entropy = pair_entropy::<F>(&data[..pairs], &data[1..]);
+    let prev_entropy = histogram_entropy::<F>(&byte_histogram(&data[..pairs]), pairs);
+    (joint_entropy - prev_entropy).max(F::zero())
+}
+
+/// Calculates the Shannon entropy of the overlapping n-grams of a byte slice.
+///
+/// Every window of `n` consecutive bytes is treated as a single symbol, which
+/// captures structure that single byte [`entropy`] misses. For `n == 1` this
+/// is identical to [`entropy`].
+///
+/// The n-grams are counted in a `HashMap` keyed on the window, so memory use
+/// grows with the number of distinct n-grams, up to `data.len() - n + 1`
+/// entries for large `n`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `n` - The length of each n-gram in bytes
+///
+/// # Returns
+///
+/// The entropy value in bits per n-gram, 0.0 if `data` is shorter than `n`.
+/// See [`ngram_entropy_per_byte`] for the value divided by `n`.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::ngram_entropy;
+///
+/// let text = String::from("ABABABAB");
+/// let e: f64 = ngram_entropy(text.as_bytes(), 2);
+/// assert!(e < 1.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn ngram_entropy<F: Float + FromPrimitive>(data: &[u8], n: usize) -> F {
+    assert!(n > 0, "n-gram length must be non-zero");
+    if n == 1 {
+        return entropy(data);
+    }
+    let grams: Vec<&[u8]> = data.windows(n).collect();
+    entropy_generic(&grams)
+}
+
+/// Calculates the n-gram entropy of a byte slice normalized per byte.
+///
+/// This is [`ngram_entropy`] divided by `n`, giving a value in bits per byte
+/// (0.0 to 8.0) that can be compared across different n-gram lengths.
+///
+/// # Panics
+///
+/// Panics if `n` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::ngram_entropy_per_byte;
+///
+/// let e: f64 = ngram_entropy_per_byte(b"ABCDABCDA", 2);
+/// assert_eq!(e, 1.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn ngram_entropy_per_byte<F: Float + FromPrimitive>(data: &[u8], n: usize) -> F {
+    ngram_entropy::<F>(data, n) / F::from_usize(n).unwrap()
+}
+
+/// Calculates the joint entropy in bits of the aligned byte pairs of `x` and `y`.
+#[cfg(feature = "alloc")]
+fn pair_entropy<F: Float + FromPrimitive>(x: &[u8], y: &[u8]) -> F {
+    let mut joint_counts = vec![0usize; 256 * 256];
+    for (&a, &b) in x.iter().zip(y) {
+        joint_counts[(a as usize) << 8 | b as usize] += 1;
+    }
+    let pairs_len = F::from_usize(x.len().min(y.len())).unwrap();
+    let mut joint_entropy = F::zero();
+    for count in joint_counts {
+        if count == 0 {
+            continue;
+        }
+        let p_xy = F::from_usize(count).unwrap() / pairs_len;
+        joint_entropy = joint_entropy - p_xy * p_xy.log2();
+    }
+    joint_entropy
+}
+
+/// Calculates the mutual information between two equal-length byte streams.
+///
+/// This is `H(X) + H(Y) - H(X, Y)` where the joint distribution is taken over
+/// aligned byte pairs `(x[i], y[i])`. Identical streams give their shared
+/// [`entropy`], while independent streams give a value near 0.0.
+///
+/// # Arguments
+///
+/// * `x` - The first byte stream
+/// * `y` - The second byte stream, the same length as `x`
+///
+/// # Returns
+///
+/// The mutual information in bits per byte (0.0 to 8.0)
+///
+/// # Panics
+///
+/// Panics if `x` and `y` have different lengths.
+///
+/// # Example
+///
+/// ```
+/// use shannon::mutual_information;
+///
+/// let i: f64 = mutual_information(b"ABAB", b"CDCD");
+/// assert_eq!(i, 1.0);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn mutual_information<F: Float + FromPrimitive>(x: &[u8], y: &[u8]) -> F {
+    assert_eq!(
+        x.len(),
+        y.len(),
+        "mutual information requires streams of equal length"
+    );
+    let information = entropy::<F>(x) + entropy::<F>(y) - pair_entropy::<F>(x, y);
+    information.max(F::zero())
+}
+
+/// Represents the kind of a region of similar entropy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionKind {
+    High,
+    Low,
+    Transition,
+}
+
+/// Represents a span of blocks between detected entropy edges.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Region<F> {
+    /// Block index of the first block in the region
+    pub start_index: usize,
+    /// Block index of the last block in the region (inclusive)
+    pub end_index: usize,
+    /// Kind of region (high, low or transition)
+    pub kind: RegionKind,
+    /// Mean normalized entropy value (0.0 to 1.0) over the region
+    pub mean_entropy: F,
+}
+
+/// Splits a sequence of entropy values into regions between detected edges.
+///
+/// Edges are found with [`detect_edges`] and each edge starts a new region
+/// which runs until the block before the next edge. Regions starting at a
+/// rising edge are [`RegionKind::High`] and those starting at a falling edge
+/// are [`RegionKind::Low`]. Any blocks before the first edge form a
+/// [`RegionKind::Transition`] region.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// A vector of regions covering every block in `entropy_values`
+///
+/// # Example
+///
+/// ```
+/// use shannon::{detect_regions, RegionKind};
+///
+/// let values = vec![(0, 7.2_f64), (1, 7.9), (2, 7.9), (3, 1.0)];
+/// let regions = detect_regions(&values, 0.95, 0.85);
+/// assert_eq!(regions.len(), 3);
+/// assert_eq!(regions[0].kind, RegionKind::Transition);
+/// assert_eq!(regions[1].kind, RegionKind::High);
+/// assert_eq!((regions[1].start_index, regions[1].end_index), (1, 2));
+/// assert_eq!(regions[2].kind, RegionKind::Low);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_regions<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+) -> Vec<Region<F>> {
+    let eight = F::from_f64(8.0).unwrap();
+    // Edges are detected by position in the slice rather than block index,
+    // which need not be unique or increasing.
+    let mut detector = EdgeDetector::new(high_threshold, low_threshold);
+    let mut starts: Vec<(usize, RegionKind)> = entropy_values
+        .iter()
+        .enumerate()
+        .filter_map(|(position, &(_, entropy))| detector.push(position, entropy))
+        .map(|edge| {
+            let kind = match edge.edge_type {
+                EdgeType::Rising => RegionKind::High,
+                EdgeType::Falling => RegionKind::Low,
+            };
+            (edge.block_index, kind)
+        })
+        .collect();
+    if !entropy_values.is_empty() && starts.first().is_none_or(|&(first, _)| first > 0) {
+        starts.insert(0, (0, RegionKind::Transition));
+    }
+
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &(start, kind))| {
+            let end = starts
+                .get(i + 1)
+                .map_or(entropy_values.len(), |&(next, _)| next);
+            let span = &entropy_values[start..end];
+            let (&(start_index, _), &(end_index, _)) = (span.first()?, span.last()?);
+            let sum = span.iter().fold(F::zero(), |acc, &(_, e)| acc + e / eight);
+            Some(Region {
+                start_index,
+                end_index,
+                kind,
+                mean_entropy: sum / F::from_usize(span.len()).unwrap(),
+            })
+        })
+        .collect()
+}
+
+/// Iterator over the Shannon entropy of consecutive blocks read from a reader.
+///
+/// Each item is the block index and the entropy in bits per byte (0-8) of
+/// that block. Every block is `block_size` bytes long except possibly the
+/// last, which holds whatever remains before the end of the reader.
+///
+/// # Example
+///
+/// ```
+/// use shannon::BlockEntropy;
+///
+/// let data: &[u8] = b"AAAABCDEF";
+/// let blocks: Vec<(usize, f64)> = BlockEntropy::new(data, 4)
+///     .collect::<std::io::Result<_>>()
+///     .unwrap();
+/// assert_eq!(blocks, vec![(0, 0.0), (1, 2.0), (2, 0.0)]);
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BlockEntropy<R, F> {
+    reader: R,
+    buffer: Vec<u8>,
+    block_index: usize,
+    _entropy: PhantomData<fn() -> F>,
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, F: Float + FromPrimitive> BlockEntropy<R, F> {
+    /// Creates an iterator reading blocks of `block_size` bytes from `reader`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is 0.
+    pub fn new(reader: R, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must be non-zero");
+        Self {
+            reader,
+            buffer: vec![0u8; block_size],
+            block_index: 0,
+            _entropy: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: Read, F: Float + FromPrimitive> Iterator for BlockEntropy<R, F> {
+    type Item = io::Result<(usize, F)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut len = 0;
+        while len < self.buffer.len() {
+            match self.reader.read(&mut self.buffer[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        if len == 0 {
+            return None;
+        }
+        let block_index = self.block_index;
+        self.block_index += 1;
+        Some(Ok((block_index, entropy(&self.buffer[..len]))))
+    }
+}
+
+/// Calculates the Shannon entropy of everything read from a reader.
+///
+/// The reader is consumed in 64 KiB chunks whose bytes are counted with an
+/// [`EntropyAccumulator`], so the data never needs to be held in memory at
+/// once. Reads interrupted by a signal are retried.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the bytes to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), or the first error
+/// returned by the reader
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_from_reader;
+///
+/// let data: &[u8] = b"AABB";
+/// let e: f64 = entropy_from_reader(data).unwrap();
+/// assert_eq!(e, 1.0);
+/// ```
+#[cfg(feature = "std")]
+pub fn entropy_from_reader<R: Read, F: Float + FromPrimitive>(reader: R) -> io::Result<F> {
+    entropy_from_reader_with_progress(reader, |_| {})
+}
+
+/// Calculates the Shannon entropy of everything read from a reader,
+/// reporting progress as it goes.
+///
+/// Behaves like [`entropy_from_reader`], but calls `progress` after each
+/// chunk with the total number of bytes read so far, e.g. to show feedback
+/// while reading a multi-GB file.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the bytes to analyze
+/// * `progress` - Called with the number of bytes read so far
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), or the first error
+/// returned by the reader
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_from_reader_with_progress;
+///
+/// let data: &[u8] = b"AABB";
+/// let mut read = 0;
+/// let e: f64 = entropy_from_reader_with_progress(data, |bytes| read = bytes).unwrap();
+/// assert_eq!(e, 1.0);
+/// assert_eq!(read, 4);
+/// ```
+#[cfg(feature = "std")]
+pub fn entropy_from_reader_with_progress<R, F, P>(mut reader: R, mut progress: P) -> io::Result<F>
+where
+    R: Read,
+    F: Float + FromPrimitive,
+    P: FnMut(u64),
+{
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut accumulator = EntropyAccumulator::new();
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => {
+                accumulator.update(&buffer[..n]);
+                progress(accumulator.len());
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(accumulator.finalize())
+}
+
+/// Calculates the Shannon entropy of each block of a byte slice in parallel.
+///
+/// The slice is split into blocks of `block_size` bytes, the last of which
+/// may be shorter, and the entropy of each block is computed on the rayon
+/// thread pool.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+///
+/// # Returns
+///
+/// A vector of (block_index, entropy) tuples in block order, with entropy in
+/// bits per byte (0.0 to 8.0)
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::par_block_entropy;
+///
+/// let values = par_block_entropy::<f64>(b"AAAABCDEF", 4);
+/// assert_eq!(values, vec![(0, 0.0), (1, 2.0), (2, 0.0)]);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_block_entropy<F: Float + FromPrimitive + Send>(
+    data: &[u8],
+    block_size: usize,
+) -> Vec<(usize, F)> {
+    use rayon::prelude::*;
+
+    data.par_chunks(block_size)
+        .enumerate()
+        .map(|(block_index, block)| (block_index, entropy(block)))
+        .collect()
+}
+
+/// Number of integers whose base 2 logarithm is cached in [`LOG2_TABLE`].
+const LOG2_TABLE_LEN: usize = 256;
+
+/// `log2(i)` for every `i` below [`LOG2_TABLE_LEN`], with `log2(0)` stored as 0.
+const LOG2_TABLE: [f64; LOG2_TABLE_LEN] = {
+    let mut table = [0.0; LOG2_TABLE_LEN];
+    let mut i = 1;
+    while i < LOG2_TABLE_LEN {
+        table[i] = const_log2(i);
+        i += 1;
+    }
+    table
+};
+
+/// Calculates `log2(n)` for `n >= 1` at compile time.
+///
+/// Splits `n` into `2^k * m` with `m` in [1, 2), then evaluates `ln(m)` with
+/// the series `2 * atanh((m - 1) / (m + 1))`, which converges quickly there.
+const fn const_log2(n: usize) -> f64 {
+    let k = usize::BITS - 1 - n.leading_zeros();
+    let m = n as f64 / (1u64 << k) as f64;
+    let z = (m - 1.0) / (m + 1.0);
+    let z2 = z * z;
+    let mut term = z;
+    let mut sum = 0.0;
+    let mut i = 0;
+    while i < 40 {
+        sum += term / (2 * i + 1) as f64;
+        term *= z2;
+        i += 1;
+    }
+    k as f64 + 2.0 * sum / core::f64::consts::LN_2
+}
+
+/// Calculates the Shannon entropy of a byte slice using a logarithm table.
+///
+/// Computes the same value as [`entropy`] but rewritten as
+/// `log2(len) - (1/len) * Σ count * log2(count)`, so that only one `log2` per
+/// distinct byte is needed and counts below 256 are looked up from a table
+/// built at compile time. Results match [`entropy`] to within floating point
+/// tolerance, not bit for bit.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::{entropy, entropy_fast};
+///
+/// let text = String::from("Hello, world!");
+/// let fast: f64 = entropy_fast(text.as_bytes());
+/// assert!((fast - entropy::<f64>(text.as_bytes())).abs() < 1e-12);
+/// ```
+pub fn entropy_fast<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    let log2 = |n: usize| match LOG2_TABLE.get(n) {
+        Some(&cached) => F::from_f64(cached).unwrap(),
+        None => F::from_usize(n).unwrap().log2(),
+    };
+    let mut sum = F::zero();
+    for count in byte_histogram(data) {
+        if count > 1 {
+            sum = sum + F::from_usize(count).unwrap() * log2(count);
+        }
+    }
+    (log2(data.len()) - sum / F::from_usize(data.len()).unwrap()).max(F::zero())
+}
+
+/// Calculates the first difference of an entropy series.
+///
+/// Each block's gradient is its entropy minus the entropy of the block before
+/// it, so large positive or negative values mark where entropy changes
+/// fastest. The output has one element per input block with the same block
+/// indices; the first block has no predecessor and its gradient is 0.0.
+///
+/// # Arguments
+///
+/// * `values` - A slice of (block_index, entropy) tuples
+///
+/// # Returns
+///
+/// A vector of (block_index, gradient) tuples
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_gradient;
+///
+/// let values = vec![(0, 1.0), (1, 7.5), (2, 7.0)];
+/// let gradient = entropy_gradient(&values);
+/// assert_eq!(gradient, vec![(0, 0.0), (1, 6.5), (2, -0.5)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn entropy_gradient<F: Float + FromPrimitive>(values: &[(usize, F)]) -> Vec<(usize, F)> {
+    let mut previous = None;
+    values
+        .iter()
+        .map(|&(block_index, entropy)| {
+            let gradient = previous.map_or(F::zero(), |p| entropy - p);
+            previous = Some(entropy);
+            (block_index, gradient)
+        })
+        .collect()
+}
+
+/// Smooths an entropy series with a centered moving average.
+///
+/// Each block's entropy is replaced by the mean of the `window` values
+/// centered on it, while its block index is kept. For an even window the
+/// extra value is taken from after the block. Near the ends of the series the
+/// window shrinks to the values available, so the output has the same length
+/// as the input.
+///
+/// # Arguments
+///
+/// * `values` - A slice of (block_index, entropy) tuples
+/// * `window` - The number of values to average over
+///
+/// # Returns
+///
+/// A vector of (block_index, smoothed entropy) tuples
+///
+/// # Panics
+///
+/// Panics if `window` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::smooth_entropy;
+///
+/// let values = vec![(0, 0.0), (1, 3.0), (2, 6.0), (3, 3.0)];
+/// let smoothed = smooth_entropy(&values, 3);
+/// assert_eq!(smoothed, vec![(0, 1.5), (1, 3.0), (2, 4.0), (3, 4.5)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn smooth_entropy<F: Float + FromPrimitive>(
+    values: &[(usize, F)],
+    window: usize,
+) -> Vec<(usize, F)> {
+    assert!(window > 0, "window must be non-zero");
+    let before = (window - 1) / 2;
+    let after = window / 2;
+    (0..values.len())
+        .map(|i| {
+            let start = i.saturating_sub(before);
+            let end = (i + after + 1).min(values.len());
+            let sum = values[start..end]
+                .iter()
+                .fold(F::zero(), |sum, &(_, entropy)| sum + entropy);
+            (values[i].0, sum / F::from_usize(end - start).unwrap())
+        })
+        .collect()
+}
+
+/// Suggests edge detection thresholds for an entropy series.
+///
+/// The normalized entropies are split into a low and a high class with Otsu's
+/// method, choosing the split that maximises the variance between the two
+/// classes. The suggested high threshold lies halfway between the split and
+/// the mean of the high class, and the low threshold halfway between the split
+/// and the mean of the low class, leaving a hysteresis gap around the split.
+/// The result can be passed straight to [`detect_edges`].
+///
+/// # Arguments
+///
+/// * `values` - A slice of (block_index, entropy) tuples, entropy in bits (0-8)
+///
+/// # Returns
+///
+/// A `(high_threshold, low_threshold)` tuple of normalized (0.0-1.0)
+/// thresholds, or `(1.0, 0.0)` if the values cannot be split because they
+/// are empty or all the same.
+///
+/// # Example
+///
+/// ```
+/// use shannon::{auto_thresholds, detect_edges};
+///
+/// let values = vec![(0, 2.0), (1, 2.2), (2, 7.9), (3, 7.8), (4, 2.1)];
+/// let (high, low) = auto_thresholds(&values);
+/// assert!(low < high);
+/// assert_eq!(detect_edges(&values, high, low).len(), 3);
+/// ```
+pub fn auto_thresholds<F: Float + FromPrimitive>(values: &[(usize, F)]) -> (F, F) {
+    const BINS: usize = 256;
+    let max_bin = F::from_usize(BINS - 1).unwrap();
+    let mut counts = [0usize; BINS];
+    let mut sums = [F::zero(); BINS];
+    for &(_, entropy) in values {
+        let normalized = (entropy / F::from_f64(8.0).unwrap())
+            .max(F::zero())
+            .min(F::one());
+        let bin = (normalized * max_bin).round().to_usize().unwrap_or(0);
+        counts[bin] += 1;
+        sums[bin] = sums[bin] + normalized;
+    }
+
+    let total_count = F::from_usize(values.len()).unwrap();
+    let total_sum = sums.iter().fold(F::zero(), |a, &b| a + b);
+    let mut best = None;
+    let mut best_variance = F::zero();
+    let (mut low_count, mut low_sum) = (F::zero(), F::zero());
+    for bin in 0..BINS - 1 {
+        low_count = low_count + F::from_usize(counts[bin]).unwrap();
+        low_sum = low_sum + sums[bin];
+        let high_count = total_count - low_count;
+        if low_count == F::zero() || high_count == F::zero() {
+            continue;
+        }
+        let low_mean = low_sum / low_count;
+        let high_mean = (total_sum - low_sum) / high_count;
+        let diff = high_mean - low_mean;
+        let variance = low_count * high_count * diff * diff;
+        // Empty bins leave the variance unchanged; split in the middle of them.
+        if variance > best_variance {
+            best_variance = variance;
+            best = Some((bin, bin, low_mean, high_mean));
+        } else if variance == best_variance
+            && let Some((_, last, _, _)) = &mut best
+        {
+            *last = bin;
+        }
+    }
+
+    match best {
+        Some((first, last, low_mean, high_mean)) => {
+            let two = F::from_f64(2.0).unwrap();
+            let bin = F::from_usize(first + last).unwrap() / two;
+            let split = (bin + F::from_f64(0.5).unwrap()) / max_bin;
+            ((split + high_mean) / two, (low_mean + split) / two)
+        }
+        None => (F::one(), F::zero()),
+    }
+}
+
+/// Finds the block with the highest entropy in a byte slice.
+///
+/// The data is split into consecutive blocks of `block_size` bytes, the last
+/// of which may be shorter. If several blocks share the highest entropy the
+/// first is returned.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - The size of each block in bytes
+///
+/// # Returns
+///
+/// The (block_index, entropy) of the highest entropy block, or `None` for
+/// empty data
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::max_entropy_block;
+///
+/// let data = b"AAAAABCDAAAA";
+/// let block = max_entropy_block::<f64>(data, 4);
+/// assert_eq!(block, Some((1, 2.0)));
+/// ```
+pub fn max_entropy_block<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+) -> Option<(usize, F)> {
+    extreme_entropy_block(data, block_size, |e, best| e > best)
+}
+
+/// Finds the block with the lowest entropy in a byte slice.
+///
+/// The data is split into consecutive blocks of `block_size` bytes, the last
+/// of which may be shorter. If several blocks share the lowest entropy the
+/// first is returned.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - The size of each block in bytes
+///
+/// # Returns
+///
+/// The (block_index, entropy) of the lowest entropy block, or `None` for
+/// empty data
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::min_entropy_block;
+///
+/// let data = b"ABCDAAAAABCD";
+/// let block = min_entropy_block::<f64>(data, 4);
+/// assert_eq!(block, Some((1, 0.0)));
+/// ```
+pub fn min_entropy_block<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+) -> Option<(usize, F)> {
+    extreme_entropy_block(data, block_size, |e, best| e < best)
+}
+
+/// Returns the first block whose entropy no later block is `better` than.
+fn extreme_entropy_block<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+    better: impl Fn(F, F) -> bool,
+) -> Option<(usize, F)> {
+    assert!(block_size > 0, "block size must be non-zero");
+    data.chunks(block_size)
+        .map(entropy::<F>)
+        .enumerate()
+        .fold(None, |best, (i, e)| match best {
+            Some((_, best_e)) if !better(e, best_e) => best,
+            _ => Some((i, e)),
+        })
+}
+
+/// Calculates the Shannon entropy of each block of a byte slice.
+///
+/// The slice is split into blocks of `block_size` bytes, the last of which
+/// may be shorter, matching the series the CLI plots and the input expected by
+/// [`detect_edges`]. Use [`BlockEntropy`] to compute the same series from a
+/// reader.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+///
+/// # Returns
+///
+/// A vector of (block_index, entropy) tuples in block order, with entropy in
+/// bits per byte (0.0 to 8.0)
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::block_entropies;
+///
+/// let values = block_entropies::<f64>(b"AAAABCDEF", 4);
+/// assert_eq!(values, vec![(0, 0.0), (1, 2.0), (2, 0.0)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn block_entropies<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+) -> Vec<(usize, F)> {
+    assert!(block_size > 0, "block size must be non-zero");
+    data.chunks(block_size).map(entropy).enumerate().collect()
+}
+
+/// Calculates the Shannon entropy of a byte slice treated as a bitstream.
+///
+/// The zero and one bits of every byte are counted and the entropy of that
+/// two-symbol distribution is returned. This exposes bit-level bias that
+/// byte entropy can hide, while patterns such as bytes that are all `0x55`
+/// still score 1.0 because their bits are balanced.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per bit (0.0 to 1.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::bit_entropy;
+///
+/// assert_eq!(bit_entropy::<f64>(&[0x00, 0xFF]), 1.0);
+/// assert_eq!(bit_entropy::<f64>(&[0x00; 4]), 0.0);
+/// ```
+pub fn bit_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    // Counted in u64 as `len * 8` overflows usize on 32-bit targets.
+    let ones: u64 = data.iter().map(|byte| u64::from(byte.count_ones())).sum();
+    let bits = data.len() as u64 * 8;
+    let mut e = F::zero();
+    for count in [ones, bits - ones] {
+        if count == 0 {
+            continue;
+        }
+        let p = F::from_u64(count).unwrap() / F::from_u64(bits).unwrap();
+        e = e - p * p.log2();
+    }
+    e
+}
+
+/// Calculates the Shannon entropy of the 4-bit nibbles of a byte slice.
+///
+/// Each byte contributes its high and low nibble as two separate symbols, so
+/// structure hidden by byte entropy in formats packing two symbols per byte
+/// shows up here.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per nibble (0.0 to 4.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::nibble_entropy;
+///
+/// // Four distinct bytes, but only two distinct nibbles
+/// let e: f64 = nibble_entropy(&[0x00, 0x01, 0x10, 0x11]);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn nibble_entropy<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[(byte >> 4) as usize] += 1;
+        counts[(byte & 0x0f) as usize] += 1;
+    }
+    histogram_entropy(&counts, data.len() * 2)
+}
+
+/// Calculates the Shannon entropy of the bytes of a slice matching a predicate.
+///
+/// Bytes for which `predicate` returns false, such as `0x00` padding, are
+/// ignored entirely: the probabilities are normalized by the number of
+/// retained bytes, not the length of `data`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `predicate` - Returns true for the bytes to include
+///
+/// # Returns
+///
+/// The entropy value in bits per retained byte (0.0 to 8.0), 0.0 if no bytes
+/// are retained
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_filtered;
+///
+/// let data = b"AB\0\0\0\0\0\0";
+/// let e: f64 = entropy_filtered(data, |byte| byte != 0);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy_filtered<F: Float + FromPrimitive>(
+    data: &[u8],
+    predicate: impl Fn(u8) -> bool,
+) -> F {
+    let mut counts = [0usize; 256];
+    let mut retained = 0;
+    for &byte in data {
+        if predicate(byte) {
+            counts[byte as usize] += 1;
+            retained += 1;
+        }
+    }
+    histogram_entropy(&counts, retained)
+}
+
+/// Calculates the Shannon entropy of a byte slice with additive smoothing.
+///
+/// `alpha` pseudo-counts are added to each of the 256 byte counts before the
+/// probabilities are calculated (add-one or Laplace smoothing for an `alpha`
+/// of 1). This gives a less biased estimate for short inputs, where the
+/// plain maximum-likelihood [`entropy`] underestimates, and leaves no byte
+/// with zero probability. An `alpha` of 0 is identical to [`entropy`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `alpha` - Non-negative pseudo-count added to every byte value
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0), tending to 8.0 as
+/// `alpha` grows
+///
+/// # Example
+///
+/// ```
+/// use shannon::{entropy, entropy_smoothed};
+///
+/// let e: f64 = entropy_smoothed(b"AAAA", 1.0);
+/// assert!(e > entropy::<f64>(b"AAAA"));
+/// assert_eq!(entropy_smoothed::<f64>(b"AABB", 0.0), 1.0);
+/// ```
+pub fn entropy_smoothed<F: Float + FromPrimitive>(data: &[u8], alpha: F) -> F {
+    if alpha == F::zero() {
+        return entropy(data);
+    }
+    let counts = byte_histogram(data);
+    let total = F::from_usize(data.len()).unwrap() + alpha * F::from_f64(256.0).unwrap();
+    let mut entropy = F::zero();
+    for &count in &counts {
+        let p_x = (F::from_usize(count).unwrap() + alpha) / total;
+        if p_x > F::zero() {
+            entropy = entropy - p_x * p_x.log2();
+        }
+    }
+    entropy
+}
+
+/// Calculates the Miller-Madow bias-corrected Shannon entropy of a byte slice.
+///
+/// The plain [`entropy`] of a finite sample systematically underestimates the
+/// entropy of its source. The Miller-Madow estimator adds `(K - 1) / (2N)`
+/// nats, where `K` is the number of distinct byte values observed and `N`
+/// the length of `data`; here the correction is converted to bits by
+/// dividing by `ln 2`. The correction shrinks as `N` grows.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The estimated entropy in bits per byte, 0.0 for an empty slice. For very
+/// short inputs this may exceed 8.0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_miller_madow;
+///
+/// // 1 bit plus (2 - 1) / (2 * 4) nats
+/// let e: f64 = entropy_miller_madow(b"AABB");
+/// assert!((e - (1.0 + 0.125 / std::f64::consts::LN_2)).abs() < 1e-12);
+/// ```
+pub fn entropy_miller_madow<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    if data.is_empty() {
+        return F::zero();
+    }
+    let observed = F::from_usize(distinct_bytes(data)).unwrap();
+    let len = F::from_usize(data.len()).unwrap();
+    let two = F::from_f64(2.0).unwrap();
+    let ln_2 = F::from_f64(core::f64::consts::LN_2).unwrap();
+    entropy::<F>(data) + (observed - F::one()) / (two * len * ln_2)
+}
+
+/// Calculates the Shannon entropy of a byte slice ignoring a fill byte.
+///
+/// Every occurrence of `exclude`, such as `0x00` or `0xFF` padding in a disk
+/// image, is left out of the count table, and the probabilities are
+/// normalized over the remaining bytes. This is [`entropy_filtered`] with a
+/// predicate rejecting `exclude`.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `exclude` - The fill byte to ignore
+///
+/// # Returns
+///
+/// The entropy value in bits per remaining byte (0.0 to 8.0), 0.0 if `data`
+/// consists only of `exclude`
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_excluding;
+///
+/// let e: f64 = entropy_excluding(b"AB\xff\xff\xff\xff", 0xff);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy_excluding<F: Float + FromPrimitive>(data: &[u8], exclude: u8) -> F {
+    entropy_filtered(data, |byte| byte != exclude)
+}
+
+/// Calculates the Shannon entropy of a byte slice after mapping each byte to
+/// a symbol.
+///
+/// This measures structure at the level of categories rather than exact
+/// values, e.g. mapping bytes to printable, control and high-bit classes. An
+/// identity map gives the same result as [`entropy`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `map` - Maps each byte to the symbol it is counted as
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to log2 of the number of distinct
+/// symbols `map` produces)
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_mapped;
+///
+/// // Letters vs everything else
+/// let e: f64 = entropy_mapped(b"ab12", |byte| byte.is_ascii_alphabetic() as u8);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy_mapped<F: Float + FromPrimitive>(data: &[u8], map: impl Fn(u8) -> u8) -> F {
+    let mut counts = [0usize; 256];
+    for &byte in data {
+        counts[map(byte) as usize] += 1;
+    }
+    histogram_entropy(&counts, data.len())
+}
+
+/// Detects only the rising edges in a sequence of entropy values.
+///
+/// The hysteresis state still follows falling edges, so the result is
+/// exactly the rising edges [`detect_edges`] would return.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// A vector of detected rising edges
+///
+/// # Example
+///
+/// ```
+/// use shannon::detect_rising_edges;
+///
+/// let values = vec![(0, 7.8_f64), (1, 2.0), (2, 7.9)];
+/// let edges = detect_rising_edges(&values, 0.95, 0.85);
+/// assert_eq!(edges.len(), 2);
+/// assert_eq!(edges[1].block_index, 2);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_rising_edges<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+) -> Vec<EntropyEdge<F>> {
+    detect_edges_of_type(
+        entropy_values,
+        high_threshold,
+        low_threshold,
+        EdgeType::Rising,
+    )
+}
+
+/// Detects only the falling edges in a sequence of entropy values.
+///
+/// The hysteresis state still follows rising edges, so the result is
+/// exactly the falling edges [`detect_edges`] would return.
+///
+/// # Arguments
+///
+/// * `entropy_values` - Slice of (block_index, entropy) tuples where entropy is in bits (0-8)
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// A vector of detected falling edges
+///
+/// # Example
+///
+/// ```
+/// use shannon::detect_falling_edges;
+///
+/// let values = vec![(0, 7.8_f64), (1, 2.0), (2, 7.9)];
+/// let edges = detect_falling_edges(&values, 0.95, 0.85);
+/// assert_eq!(edges.len(), 1);
+/// assert_eq!(edges[0].block_index, 1);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_falling_edges<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+) -> Vec<EntropyEdge<F>> {
+    detect_edges_of_type(
+        entropy_values,
+        high_threshold,
+        low_threshold,
+        EdgeType::Falling,
+    )
+}
+
+/// Runs the edge detector over `entropy_values`, keeping edges of one type.
+#[cfg(feature = "alloc")]
+fn detect_edges_of_type<F: Float + FromPrimitive>(
+    entropy_values: &[(usize, F)],
+    high_threshold: F,
+    low_threshold: F,
+    edge_type: EdgeType,
+) -> Vec<EntropyEdge<F>> {
+    let mut detector = EdgeDetector::new(high_threshold, low_threshold);
+    entropy_values
+        .iter()
+        .filter_map(|&(block_index, entropy)| {
+            detector.push_only(block_index, entropy, Some(edge_type))
+        })
+        .collect()
+}
+
+/// A broad class of data guessed from its byte distribution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataClass {
+    /// Mostly printable ASCII and whitespace
+    Text,
+    /// High entropy with a histogram too uneven to be random
+    Compressed,
+    /// High entropy with a histogram as even as random data
+    Encrypted,
+    /// Medium entropy using many byte values, with a noticeable share of zeros
+    Executable,
+    /// None of the other classes, or empty data
+    Unknown,
+}
+
+/// The thresholds used by [`classify_data_with`].
+///
+/// [`Default`] gives the values used by [`classify_data`]. Rules are checked
+/// in the order of the fields below and the first that matches wins.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassifyThresholds {
+    /// Minimum fraction of printable ASCII or whitespace bytes for
+    /// [`DataClass::Text`] (default 0.95)
+    pub text_ratio: f64,
+    /// Minimum entropy in bits per byte for [`DataClass::Compressed`] or
+    /// [`DataClass::Encrypted`] (default 7.5)
+    pub high_entropy: f64,
+    /// Maximum [`chi_square_uniform`] statistic for high entropy data to be
+    /// [`DataClass::Encrypted`] rather than [`DataClass::Compressed`]
+    /// (default 350, random data averages 255)
+    pub random_chi_square: f64,
+    /// Minimum entropy in bits per byte for [`DataClass::Executable`]
+    /// (default 4.5)
+    pub executable_entropy: f64,
+    /// Minimum number of distinct bytes for [`DataClass::Executable`]
+    /// (default 128)
+    pub executable_distinct_bytes: usize,
+    /// Minimum fraction of zero bytes for [`DataClass::Executable`]
+    /// (default 0.05)
+    pub executable_zero_ratio: f64,
+}
+
+impl Default for ClassifyThresholds {
+    fn default() -> Self {
+        Self {
+            text_ratio: 0.95,
+            high_entropy: 7.5,
+            random_chi_square: 350.0,
+            executable_entropy: 4.5,
+            executable_distinct_bytes: 128,
+            executable_zero_ratio: 0.05,
+        }
+    }
+}
+
+/// Guesses the class of a byte slice from its entropy and byte histogram.
+///
+/// Uses the default [`ClassifyThresholds`]; see [`classify_data_with`] to
+/// change them. This is a quick heuristic, not a file format parser.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The guessed [`DataClass`], [`DataClass::Unknown`] for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::{classify_data, DataClass};
+///
+/// let text = "The quick brown fox jumps over the lazy dog.".repeat(10);
+/// assert_eq!(classify_data(text.as_bytes()), DataClass::Text);
+/// ```
+pub fn classify_data(data: &[u8]) -> DataClass {
+    classify_data_with(data, &ClassifyThresholds::default())
+}
+
+/// Guesses the class of a byte slice using the given thresholds.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `thresholds` - The thresholds of each classification rule
+///
+/// # Returns
+///
+/// The guessed [`DataClass`], [`DataClass::Unknown`] for empty data
+///
+/// # Example
+///
+/// ```
+/// use shannon::{classify_data_with, ClassifyThresholds, DataClass};
+///
+/// let thresholds = ClassifyThresholds {
+///     text_ratio: 0.5,
+///     ..ClassifyThresholds::default()
+/// };
+/// assert_eq!(classify_data_with(b"ab\0\0", &thresholds), DataClass::Text);
+/// ```
+pub fn classify_data_with(data: &[u8], thresholds: &ClassifyThresholds) -> DataClass {
+    if data.is_empty() {
+        return DataClass::Unknown;
+    }
+    let counts = byte_histogram(data);
+    let len = data.len() as f64;
+    let printable: usize = counts
+        .iter()
+        .enumerate()
+        .filter(|&(byte, _)| {
+            let byte = byte as u8;
+            byte.is_ascii_graphic() || byte.is_ascii_whitespace()
+        })
+        .map(|(_, &count)| count)
+        .sum();
+    if printable as f64 / len >= thresholds.text_ratio {
+        return DataClass::Text;
+    }
+
+    let e: f64 = histogram_entropy(&counts, data.len());
+    if e >= thresholds.high_entropy {
+        return if chi_square_uniform::<f64>(data) <= thresholds.random_chi_square {
+            DataClass::Encrypted
+        } else {
+            DataClass::Compressed
+        };
+    }
+
+    let distinct = counts.iter().filter(|&&count| count > 0).count();
+    if e >= thresholds.executable_entropy
+        && distinct >= thresholds.executable_distinct_bytes
+        && counts[0] as f64 / len >= thresholds.executable_zero_ratio
+    {
+        return DataClass::Executable;
+    }
+    DataClass::Unknown
+}
+
+/// Calculates the Shannon entropy of two byte slices XORed together.
+///
+/// Aligned bytes of `a` and `b` are XORed and the entropy of the resulting
+/// stream is returned. Independent random streams give close to 8 bits per
+/// byte, while low entropy suggests the inputs are correlated, e.g. two
+/// messages encrypted with the same keystream. If the slices differ in length
+/// only the overlapping prefix is used.
+///
+/// # Arguments
+///
+/// * `a` - The first byte slice
+/// * `b` - The second byte slice
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0) of the XORed prefix
+///
+/// # Example
+///
+/// ```
+/// use shannon::xor_entropy;
+///
+/// let e: f64 = xor_entropy(b"ABAB", b"BABAextra");
+/// assert_eq!(e, 0.0);
+/// ```
+pub fn xor_entropy<F: Float + FromPrimitive>(a: &[u8], b: &[u8]) -> F {
+    let mut counts = [0usize; 256];
+    for (x, y) in a.iter().zip(b) {
+        counts[(x ^ y) as usize] += 1;
+    }
+    histogram_entropy(&counts, a.len().min(b.len()))
+}
+
+/// Calculates the Shannon entropy of each byte position across fixed size records.
+///
+/// The data is split into records of `record_size` bytes, and for each
+/// position within a record the entropy of the values at that position in
+/// every record is returned. Constant fields score 0.0 and random fields
+/// score high. A trailing partial record is ignored so that every position
+/// is measured over the same number of records.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `record_size` - The size of each record in bytes
+///
+/// # Returns
+///
+/// A vector of `record_size` entropy values in bits per byte (0.0 to 8.0),
+/// all 0.0 if `data` is shorter than one record
+///
+/// # Panics
+///
+/// Panics if `record_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::positional_entropy;
+///
+/// // A constant tag byte followed by a counter
+/// let data = b"T0T1T2T3x";
+/// let e: Vec<f64> = positional_entropy(data, 2);
+/// assert_eq!(e, vec![0.0, 2.0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn positional_entropy<F: Float + FromPrimitive>(data: &[u8], record_size: usize) -> Vec<F> {
+    assert!(record_size > 0, "record size must be non-zero");
+    let mut counts = vec![[0usize; 256]; record_size];
+    let records = data.chunks_exact(record_size);
+    let record_count = records.len();
+    for record in records {
+        for (position, &byte) in record.iter().enumerate() {
+            counts[position][byte as usize] += 1;
+        }
+    }
+    counts
+        .iter()
+        .map(|counts| histogram_entropy(counts, record_count))
+        .collect()
+}
+
+/// Calculates the approximate entropy (ApEn) of a byte slice.
+///
+/// Approximate entropy measures how often patterns of `m` consecutive bytes
+/// that are similar stay similar when extended by one more byte. Two patterns
+/// are similar when no pair of aligned bytes differs by more than `r`.
+/// Regular data, such as a repeating counter, scores close to 0.0 even
+/// though its byte histogram is flat and its Shannon [`entropy`] is high.
+/// The result is in natural units and the calculation is quadratic in the
+/// length of the data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `m` - The length of the patterns to compare
+/// * `r` - The largest difference between aligned bytes of similar patterns
+///
+/// # Returns
+///
+/// The approximate entropy, 0.0 if `data` is shorter than `m + 1` bytes
+///
+/// # Example
+///
+/// ```
+/// use shannon::approximate_entropy;
+///
+/// let periodic = b"ABCD".repeat(50);
+/// let apen: f64 = approximate_entropy(&periodic, 2, 0.0);
+/// assert!(apen < 0.01);
+/// ```
+pub fn approximate_entropy<F: Float + FromPrimitive>(data: &[u8], m: usize, r: F) -> F {
+    if data.len() < m + 1 {
+        return F::zero();
+    }
+    apen_phi(data, m, r) - apen_phi(data, m + 1, r)
+}
+
+/// The mean log fraction of length `m` patterns similar to each pattern.
+fn apen_phi<F: Float + FromPrimitive>(data: &[u8], m: usize, r: F) -> F {
+    let patterns = data.len() - m + 1;
+    let total = F::from_usize(patterns).unwrap();
+    let mut sum = F::zero();
+    for i in 0..patterns {
+        let matches = (0..patterns)
+            .filter(|&j| similar(&data[i..i + m], &data[j..j + m], r))
+            .count();
+        sum = sum + (F::from_usize(matches).unwrap() / total).ln();
+    }
+    sum / total
+}
+
+/// Whether no pair of aligned bytes of `a` and `b` differs by more than `r`.
+fn similar<F: Float + FromPrimitive>(a: &[u8], b: &[u8], r: F) -> bool {
+    let max_diff = a.iter().zip(b).map(|(x, y)| x.abs_diff(*y)).max();
+    max_diff.is_none_or(|diff| F::from_u8(diff).unwrap() <= r)
+}
+
+/// Calculates the sample entropy (SampEn) of a byte slice.
+///
+/// Sample entropy is `-ln(A / B)`, where `B` counts pairs of similar patterns
+/// of `m` consecutive bytes and `A` counts those pairs that are still similar
+/// when extended by one byte, using the same similarity rule as
+/// [`approximate_entropy`]. Unlike approximate entropy a pattern is never
+/// compared with itself, which makes sample entropy less biased on short
+/// data. The result is in natural units and the calculation is quadratic in
+/// the length of the data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `m` - The length of the patterns to compare
+/// * `r` - The largest difference between aligned bytes of similar patterns
+///
+/// # Returns
+///
+/// The sample entropy. If no extended pattern pairs match (`A` is 0) the
+/// logarithm is undefined and infinity is returned, which includes data too
+/// short to hold two patterns of `m + 1` bytes.
+///
+/// # Example
+///
+/// ```
+/// use shannon::sample_entropy;
+///
+/// let periodic = b"ABCD".repeat(50);
+/// let sampen: f64 = sample_entropy(&periodic, 2, 0.0);
+/// assert!(sampen < 0.01);
+///
+/// let distinct: f64 = sample_entropy(b"ABCDEFGH", 2, 0.0);
+/// assert!(distinct.is_infinite());
+/// ```
+pub fn sample_entropy<F: Float + FromPrimitive>(data: &[u8], m: usize, r: F) -> F {
+    if data.len() < m + 2 {
+        return F::infinity();
+    }
+    // Both counts use the same N - m patterns so that they are comparable.
+    let patterns = data.len() - m;
+    let mut a = 0usize;
+    let mut b = 0usize;
+    for i in 0..patterns {
+        for j in i + 1..patterns {
+            if similar(&data[i..i + m], &data[j..j + m], r) {
+                b += 1;
+                if similar(&data[i + m..=i + m], &data[j + m..=j + m], r) {
+                    a += 1;
+                }
+            }
+        }
+    }
+    if a == 0 {
+        return F::infinity();
+    }
+    F::zero() - (F::from_usize(a).unwrap() / F::from_usize(b).unwrap()).ln()
+}
+
+/// Detects shifts in the mean level of an entropy series using CUSUM.
+///
+/// Keeps cumulative sums of how far each value lies above and below the mean
+/// of the current segment, ignoring deviations smaller than half of
+/// `sensitivity`. When either sum exceeds `sensitivity` a change point is
+/// reported at the block where that sum started growing, and a new segment
+/// starts there. Unlike [`detect_edges`] this finds changes in level that do
+/// not cross a fixed threshold, e.g. from 3 to 5 bits per byte.
+///
+/// # Arguments
+///
+/// * `values` - A slice of (block_index, entropy) tuples
+/// * `sensitivity` - The smallest shift in mean entropy to detect, in the
+///   units of `values`; smaller values detect smaller shifts but react more
+///   to noise
+///
+/// # Returns
+///
+/// The block indices at which the mean entropy changes
+///
+/// # Panics
+///
+/// Panics if `sensitivity` is not positive.
+///
+/// # Example
+///
+/// ```
+/// use shannon::detect_changepoints;
+///
+/// let values: Vec<(usize, f64)> = (0..30)
+///     .map(|i| (i, if i < 10 { 3.0 } else if i < 20 { 5.0 } else { 4.0 }))
+///     .collect();
+/// assert_eq!(detect_changepoints(&values, 0.5), vec![10, 20]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn detect_changepoints<F: Float + FromPrimitive>(
+    values: &[(usize, F)],
+    sensitivity: F,
+) -> Vec<usize> {
+    assert!(sensitivity > F::zero(), "sensitivity must be positive");
+    let drift = sensitivity / F::from_f64(2.0).unwrap();
+    let mut changepoints = Vec::new();
+    let (mut segment_sum, mut segment_len) = (F::zero(), 0);
+    let (mut high_sum, mut low_sum) = (F::zero(), F::zero());
+    let (mut high_start, mut low_start) = (0, 0);
+
+    let mut i = 0;
+    while i < values.len() {
+        let entropy = values[i].1;
+        if segment_len == 0 {
+            segment_sum = entropy;
+            segment_len = 1;
+            i += 1;
+            continue;
+        }
+        let deviation = entropy - segment_sum / F::from_usize(segment_len).unwrap();
+        if high_sum == F::zero() {
+            high_start = i;
+        }
+        if low_sum == F::zero() {
+            low_start = i;
+        }
+        high_sum = (high_sum + deviation - drift).max(F::zero());
+        low_sum = (low_sum - deviation - drift).max(F::zero());
+
+        if high_sum > sensitivity || low_sum > sensitivity {
+            let start = if high_sum > sensitivity {
+                high_start
+            } else {
+                low_start
+            };
+            changepoints.push(values[start].0);
+            // Restart from the change point with a fresh segment.
+            segment_len = 0;
+            high_sum = F::zero();
+            low_sum = F::zero();
+            i = start;
+        } else {
+            segment_sum = segment_sum + entropy;
+            segment_len += 1;
+            i += 1;
+        }
+    }
+    changepoints
+}
+
+/// Calculates the weighted Shannon entropy of a byte slice.
+///
+/// Computes `-Σ w_i * p_i * log2(p_i)`, where `w_i` is the weight of byte
+/// value `i`, so that some bytes can count for more or less than others, e.g.
+/// a weight of 0.0 for padding. With every weight 1.0 this is [`entropy`].
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `weights` - The weight of each byte value
+///
+/// # Returns
+///
+/// The weighted entropy in bits per byte
+///
+/// # Example
+///
+/// ```
+/// use shannon::weighted_entropy;
+///
+/// let mut weights = [1.0_f64; 256];
+/// weights[0] = 0.0;
+/// let e = weighted_entropy(b"AB\0\0", &weights);
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn weighted_entropy<F: Float + FromPrimitive>(data: &[u8], weights: &[F; 256]) -> F {
+    let data_len = F::from_usize(data.len()).unwrap();
+    let mut entropy = F::zero();
+    for (count, &weight) in byte_histogram(data).iter().zip(weights) {
+        if *count == 0 {
+            continue;
+        }
+        let p_x = F::from_usize(*count).unwrap() / data_len;
+        entropy = entropy - weight * p_x * p_x.log2();
+    }
+    entropy
+}
+
+/// Calculates the mean absolute difference between two entropy series.
+///
+/// Blocks are compared by position, so the `n`th value of `a` is compared
+/// with the `n`th value of `b`. If the series differ in length only the
+/// common prefix is compared; blocks beyond the end of the shorter series
+/// are ignored.
+///
+/// # Arguments
+///
+/// * `a` - A slice of (block_index, entropy) tuples
+/// * `b` - A slice of (block_index, entropy) tuples
+///
+/// # Returns
+///
+/// The mean absolute difference in the units of the entropy values, 0.0 if
+/// either series is empty
+///
+/// # Example
+///
+/// ```
+/// use shannon::series_distance;
+///
+/// let before = vec![(0, 2.0_f64), (1, 7.5), (2, 4.0)];
+/// let after = vec![(0, 3.0), (1, 7.5)];
+/// assert_eq!(series_distance(&before, &after), 0.5);
+/// ```
+pub fn series_distance<F: Float + FromPrimitive>(a: &[(usize, F)], b: &[(usize, F)]) -> F {
+    let len = a.len().min(b.len());
+    if len == 0 {
+        return F::zero();
+    }
+    let sum = a
+        .iter()
+        .zip(b)
+        .fold(F::zero(), |sum, (&(_, x), &(_, y))| sum + (x - y).abs());
+    sum / F::from_usize(len).unwrap()
+}
+
+/// Calculates the autocorrelation of an entropy series.
+///
+/// The value at lag `k` compares each block with the block `k` places later,
+/// normalized so that lag 0 is 1.0. Peaks at a lag `k > 0` mean the entropy
+/// pattern repeats every `k` blocks, as with files made of fixed size
+/// records.
+///
+/// # Arguments
+///
+/// * `values` - A slice of (block_index, entropy) tuples
+/// * `max_lag` - The largest lag to calculate
+///
+/// # Returns
+///
+/// A vector of `max_lag + 1` autocorrelations (-1.0 to 1.0), indexed by lag.
+/// Lags of at least the length of the series, and every lag of a constant
+/// series, are 0.0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_autocorrelation;
+///
+/// let values: Vec<(usize, f64)> = (0..12).map(|i| (i, [1.0, 7.0, 7.0][i % 3])).collect();
+/// let r = entropy_autocorrelation(&values, 3);
+/// assert_eq!(r[0], 1.0);
+/// assert!(r[3] > r[1] && r[3] > r[2]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn entropy_autocorrelation<F: Float + FromPrimitive>(
+    values: &[(usize, F)],
+    max_lag: usize,
+) -> Vec<F> {
+    let mut correlations = vec![F::zero(); max_lag + 1];
+    if values.is_empty() {
+        return correlations;
+    }
+    let mean = values.iter().fold(F::zero(), |sum, &(_, e)| sum + e)
+        / F::from_usize(values.len()).unwrap();
+    let deviations: Vec<F> = values.iter().map(|&(_, e)| e - mean).collect();
+    let variance = deviations.iter().fold(F::zero(), |sum, &d| sum + d * d);
+    if variance == F::zero() {
+        return correlations;
+    }
+    for (lag, correlation) in correlations.iter_mut().enumerate().take(values.len()) {
+        let sum = deviations
+            .iter()
+            .zip(&deviations[lag..])
+            .fold(F::zero(), |sum, (&x, &y)| sum + x * y);
+        *correlation = sum / variance;
+    }
+    correlations
+}
+
+/// Calculates the Shannon entropy of a byte slice using Kahan summation.
+///
+/// Computes the same value as [`entropy`], but carries the rounding error of
+/// each addition into the next so that summing the 256 terms loses less
+/// precision. This matters mostly for `f32`, where a long skewed input gives
+/// many small terms next to a large one; with `f64` [`entropy`] is normally
+/// accurate enough.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_kahan;
+///
+/// let text = String::from("AABB");
+/// let e: f32 = entropy_kahan(text.as_bytes());
+/// assert_eq!(e, 1.0);
+/// ```
+pub fn entropy_kahan<F: Float + FromPrimitive>(data: &[u8]) -> F {
+    histogram_entropy_kahan(&byte_histogram(data), data.len())
+}
+
+/// [`histogram_entropy`] with the terms added by Kahan summation.
+fn histogram_entropy_kahan<F: Float + FromPrimitive>(counts: &[usize; 256], len: usize) -> F {
+    let data_len = F::from_usize(len).unwrap();
+    let mut sum = F::zero();
+    let mut compensation = F::zero();
+    for &count in counts {
+        if count == 0 {
+            continue;
+        }
+        let p_x = F::from_usize(count).unwrap() / data_len;
+        let term = p_x * p_x.log2() - compensation;
+        let next = sum + term;
+        compensation = (next - sum) - term;
+        sum = next;
+    }
+    F::zero() - sum
+}
+
+/// Summary statistics of a series of block entropies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntropyStats<F> {
+    /// Lowest block entropy
+    pub min: F,
+    /// Highest block entropy
+    pub max: F,
+    /// Mean block entropy
+    pub mean: F,
+    /// Median block entropy, the mean of the middle two for an even count
+    pub median: F,
+    /// Population standard deviation of the block entropies
+    pub std_dev: F,
+}
+
+impl<F: Float + FromPrimitive> EntropyStats<F> {
+    /// Calculates the statistics of an existing (block_index, entropy) series.
+    ///
+    /// Every statistic is 0.0 for an empty series. NaN entropies are ordered
+    /// above every number, so any NaN makes `max`, `mean` and `std_dev` NaN,
+    /// while `min` and `median` are taken from that ordering (and are NaN only
+    /// if at least half of the values are).
+    #[cfg(feature = "alloc")]
+    pub fn from_values(values: &[(usize, F)]) -> Self {
+        if values.is_empty() {
+            return Self {
+                min: F::zero(),
+                max: F::zero(),
+                mean: F::zero(),
+                median: F::zero(),
+                std_dev: F::zero(),
+            };
+        }
+        let mut sorted: Vec<F> = values.iter().map(|&(_, e)| e).collect();
+        sorted.sort_by(|&a, &b| cmp_nan_last(a, b));
+        let len = F::from_usize(sorted.len()).unwrap();
+        let mean = sorted.iter().fold(F::zero(), |sum, &e| sum + e) / len;
+        let variance = sorted
+            .iter()
+            .fold(F::zero(), |sum, &e| sum + (e - mean) * (e - mean))
+            / len;
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / F::from_f64(2.0).unwrap()
+        } else {
+            sorted[middle]
+        };
+        Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean,
+            median,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+/// Calculates summary statistics of the block entropies of a byte slice.
+///
+/// The blocks are those of [`block_entropies`]. The statistics are a compact
+/// fingerprint for comparing files without the full series.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+///
+/// # Returns
+///
+/// The min, max, mean, median and standard deviation of the block entropies
+/// in bits per byte, all 0.0 for empty data
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_stats;
+///
+/// let stats = entropy_stats::<f64>(b"AAAABCDEABAB", 4);
+/// assert_eq!(stats.min, 0.0);
+/// assert_eq!(stats.max, 2.0);
+/// assert_eq!(stats.median, 1.0);
+/// assert_eq!(stats.mean, 1.0);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn entropy_stats<F: Float + FromPrimitive>(data: &[u8], block_size: usize) -> EntropyStats<F> {
+    EntropyStats::from_values(&block_entropies(data, block_size))
+}
+
+/// Finds the first block of a byte slice whose entropy exceeds a threshold.
+///
+/// Blocks are those of [`block_entropies`], but the scan stops at the first
+/// block with entropy strictly above `threshold`, so the entropy of later
+/// blocks is never calculated.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+/// * `threshold` - The entropy in bits per byte (0.0 to 8.0) to exceed
+///
+/// # Returns
+///
+/// The (block_index, entropy) of the first block above `threshold`, or `None`
+/// if no block qualifies
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::first_block_above;
+///
+/// let data = b"AAAAAABBABCD";
+/// assert_eq!(first_block_above::<f64>(data, 4, 0.5), Some((1, 1.0)));
+/// assert_eq!(first_block_above::<f64>(data, 4, 2.0), None);
+/// ```
+pub fn first_block_above<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+    threshold: F,
+) -> Option<(usize, F)> {
+    assert!(block_size > 0, "block size must be non-zero");
+    data.chunks(block_size)
+        .map(entropy::<F>)
+        .enumerate()
+        .find(|&(_, e)| e > threshold)
+}
+
+/// Calculates the Shannon entropy of each of many byte slices.
+///
+/// Equivalent to calling [`entropy`] on each slice in turn. With the `rayon`
+/// feature [`par_entropy_batch`] computes the same results in parallel.
+///
+/// # Arguments
+///
+/// * `slices` - The byte slices to analyze
+///
+/// # Returns
+///
+/// A vector of entropy values in bits per byte (0.0 to 8.0), in the same
+/// order as `slices`
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_batch;
+///
+/// let records: [&[u8]; 3] = [b"AAAA", b"AABB", b"ABCD"];
+/// let e: Vec<f64> = entropy_batch(&records);
+/// assert_eq!(e, vec![0.0, 1.0, 2.0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn entropy_batch<F: Float + FromPrimitive>(slices: &[&[u8]]) -> Vec<F> {
+    slices.iter().map(|slice| entropy(slice)).collect()
+}
+
+/// Calculates the Shannon entropy of each of many byte slices in parallel.
+///
+/// Behaves like [`entropy_batch`], but the slices are spread over the rayon
+/// thread pool.
+///
+/// # Arguments
+///
+/// * `slices` - The byte slices to analyze
+///
+/// # Returns
+///
+/// A vector of entropy values in bits per byte (0.0 to 8.0), in the same
+/// order as `slices`
+///
+/// # Example
+///
+/// ```
+/// use shannon::par_entropy_batch;
+///
+/// let records: [&[u8]; 3] = [b"AAAA", b"AABB", b"ABCD"];
+/// let e: Vec<f64> = par_entropy_batch(&records);
+/// assert_eq!(e, vec![0.0, 1.0, 2.0]);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_entropy_batch<F: Float + FromPrimitive + Send>(slices: &[&[u8]]) -> Vec<F> {
+    use rayon::prelude::*;
+
+    slices.par_iter().map(|slice| entropy(slice)).collect()
+}
+
+/// Calculates the absolute difference in overall entropy between two byte
+/// slices.
+///
+/// # Arguments
+///
+/// * `prev` - The earlier snapshot of the data
+/// * `curr` - The later snapshot of the data
+///
+/// # Returns
+///
+/// The absolute difference of their entropies in bits per byte (0.0 to 8.0)
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_delta;
+///
+/// let e: f64 = entropy_delta(b"AAAA", b"ABCD");
+/// assert_eq!(e, 2.0);
+/// ```
+pub fn entropy_delta<F: Float + FromPrimitive>(prev: &[u8], curr: &[u8]) -> F {
+    entropy_change(prev, curr, F::zero()).delta.abs()
+}
+
+/// Represents the direction of a change in entropy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntropyTrend {
+    Rose,
+    Fell,
+    Stable,
+}
+
+/// Summary of the change in overall entropy between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntropyChange<F> {
+    /// Entropy of the earlier snapshot in bits per byte
+    pub previous: F,
+    /// Entropy of the later snapshot in bits per byte
+    pub current: F,
+    /// Signed change in entropy, `current - previous`
+    pub delta: F,
+    /// Whether entropy rose, fell or stayed within the tolerance
+    pub trend: EntropyTrend,
+}
+
+/// Compares the overall entropy of two snapshots of a data stream.
+///
+/// The change is [`EntropyTrend::Stable`] if the entropies differ by no more
+/// than `tolerance`, so a monitor can alert on e.g. encryption being turned
+/// on without reacting to noise.
+///
+/// # Arguments
+///
+/// * `prev` - The earlier snapshot of the data
+/// * `curr` - The later snapshot of the data
+/// * `tolerance` - Largest change in bits per byte still considered stable
+///
+/// # Returns
+///
+/// The entropies of both snapshots and the direction of the change
+///
+/// # Example
+///
+/// ```
+/// use shannon::{entropy_change, EntropyTrend};
+///
+/// let change = entropy_change::<f64>(b"AAAA", b"ABCD", 0.5);
+/// assert_eq!(change.delta, 2.0);
+/// assert_eq!(change.trend, EntropyTrend::Rose);
+/// assert_eq!(entropy_change::<f64>(b"ABAB", b"CDCD", 0.5).trend, EntropyTrend::Stable);
+/// ```
+pub fn entropy_change<F: Float + FromPrimitive>(
+    prev: &[u8],
+    curr: &[u8],
+    tolerance: F,
+) -> EntropyChange<F> {
+    let previous: F = entropy(prev);
+    let current: F = entropy(curr);
+    let delta = current - previous;
+    let trend = if delta > tolerance {
+        EntropyTrend::Rose
+    } else if delta < -tolerance {
+        EntropyTrend::Fell
+    } else {
+        EntropyTrend::Stable
+    };
+    EntropyChange {
+        previous,
+        current,
+        delta,
+        trend,
+    }
+}
+
+/// Splits a byte slice into variable-length segments at entropy edges.
+///
+/// The block entropies of `data` are passed to [`detect_edges`], and `data`
+/// is cut at the start of each edge's block, so segment boundaries always
+/// snap to multiples of `block_size`. An edge on the first block does not
+/// start a new segment.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to segment
+/// * `block_size` - Size of each block in bytes
+/// * `high_threshold` - Normalized threshold (0.0-1.0) for detecting rising edges
+/// * `low_threshold` - Normalized threshold (0.0-1.0) for detecting falling edges
+///
+/// # Returns
+///
+/// The consecutive segments of `data`, which together cover all of it, or no
+/// segments if `data` is empty
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::segment_at_edges;
+///
+/// let mut data = vec![0u8; 256];
+/// data.extend(0..=255);
+/// let segments = segment_at_edges(&data, 128, 0.8_f64, 0.5);
+/// assert_eq!(segments.len(), 2);
+/// assert_eq!(segments[0], &[0; 256][..]);
+/// assert_eq!(segments[1].len(), 256);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn segment_at_edges<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+    high_threshold: F,
+    low_threshold: F,
+) -> Vec<&[u8]> {
+    let values: Vec<(usize, F)> = block_entropies(data, block_size);
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for edge in detect_edges(&values, high_threshold, low_threshold) {
+        let offset = edge.byte_offset(block_size);
+        if offset > start {
+            segments.push(&data[start..offset]);
+            start = offset;
+        }
+    }
+    if start < data.len() {
+        segments.push(&data[start..]);
+    }
+    segments
+}
+
+/// Calculates the entropy of all data up to the end of each block.
+///
+/// Entry `i` is the entropy of `data[..(i + 1) * block_size]` (or of all of
+/// `data` for the last, possibly partial, block), showing how the running
+/// estimate stabilizes as more of the data is seen. Pair it with
+/// [`block_entropies`] for the entropy of each block on its own, and
+/// [`cumulative_entropy_reverse`] for the running estimate from the end.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+///
+/// # Returns
+///
+/// A vector of (block_index, entropy) tuples where entropy is in bits per
+/// byte (0-8)
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::cumulative_entropy;
+///
+/// let values: Vec<(usize, f64)> = cumulative_entropy(b"AAAABBBB", 4);
+/// assert_eq!(values, vec![(0, 0.0), (1, 1.0)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn cumulative_entropy<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+) -> Vec<(usize, F)> {
+    assert!(block_size > 0, "block size must be non-zero");
+    let mut accumulator = EntropyAccumulator::new();
+    data.chunks(block_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            accumulator.update(chunk);
+            (i, accumulator.finalize())
+        })
+        .collect()
+}
+
+/// Calculates the entropy of all data from the start of each block to the
+/// end.
+///
+/// Entry `i` is the entropy of `data[i * block_size..]`, the counterpart of
+/// [`cumulative_entropy`] accumulated backwards from the end of the data.
+///
+/// # Arguments
+///
+/// * `data` - A byte slice to analyze
+/// * `block_size` - Size of each block in bytes
+///
+/// # Returns
+///
+/// A vector of (block_index, entropy) tuples in ascending block order where
+/// entropy is in bits per byte (0-8)
+///
+/// # Panics
+///
+/// Panics if `block_size` is 0.
+///
+/// # Example
+///
+/// ```
+/// use shannon::cumulative_entropy_reverse;
+///
+/// let values: Vec<(usize, f64)> = cumulative_entropy_reverse(b"AAAABBBB", 4);
+/// assert_eq!(values, vec![(0, 1.0), (1, 0.0)]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn cumulative_entropy_reverse<F: Float + FromPrimitive>(
+    data: &[u8],
+    block_size: usize,
+) -> Vec<(usize, F)> {
+    assert!(block_size > 0, "block size must be non-zero");
+    let mut accumulator = EntropyAccumulator::new();
+    let mut values: Vec<(usize, F)> = data
+        .chunks(block_size)
+        .enumerate()
+        .rev()
+        .map(|(i, chunk)| {
+            accumulator.update(chunk);
+            (i, accumulator.finalize())
+        })
+        .collect();
+    values.reverse();
+    values
+}
+
+/// Calculates the Shannon entropy of a ring buffer.
+///
+/// The logical sequence is `buffer[start..]` followed by `buffer[..start]`,
+/// as when a capture wraps around the end of its backing array. Entropy does
+/// not depend on the order of the bytes, so this is the same as [`entropy`]
+/// of the whole buffer; the function exists so callers can pass their ring
+/// buffer and read position as they are, without unwrapping it into a copy.
+///
+/// # Arguments
+///
+/// * `buffer` - The backing array of the ring buffer
+/// * `start` - Index in `buffer` of the first byte of the logical sequence
+///
+/// # Returns
+///
+/// The entropy value in bits per byte (0.0 to 8.0)
+///
+/// # Panics
+///
+/// Panics if `start` is greater than `buffer.len()`.
+///
+/// # Example
+///
+/// ```
+/// use shannon::entropy_wrapped;
+///
+/// // The logical sequence is "ABCD"
+/// let e: f64 = entropy_wrapped(b"CDAB", 2);
+/// assert_eq!(e, 2.0);
+/// ```
+pub fn entropy_wrapped<F: Float + FromPrimitive>(buffer: &[u8], start: usize) -> F {
+    let (tail, head) = buffer.split_at(start);
+    let mut counts = byte_histogram(head);
+    for (count, extra) in counts.iter_mut().zip(byte_histogram(tail)) {
+        *count += extra;
+    }
+    entropy_from_counts(&counts)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    #[test]
+    fn skewed() {
+        let a = String::from("ABAB");
+        let b = String::from("AAAB");
         let c = String::from("AAAAAB");
         let s_a: f64 = entropy(a.as_bytes());
         let s_b: f64 = entropy(b.as_bytes());
@@ -225,4 +4451,1349 @@ mod test {
         assert!(s_c < s_d);
         assert_eq!(s_a, s_e);
     }
+    #[test]
+    fn windowed() {
+        let data = b"AAAABBBBC";
+        let values = windowed_entropy::<f32>(data, 4, 4);
+        assert_eq!(values, vec![(0, 0.0), (4, 0.0)]);
+        let values = windowed_entropy::<f32>(data, 2, 3);
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[1], (3, 1.0));
+        assert!(windowed_entropy::<f32>(data, 10, 1).is_empty());
+    }
+    #[test]
+    fn accumulator() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let mut acc = EntropyAccumulator::new();
+        for chunk in data.chunks(5) {
+            acc.update(chunk);
+        }
+        assert_eq!(acc.len(), data.len() as u64);
+        assert_eq!(acc.counts()[b'o' as usize], 4);
+        assert_eq!(acc.finalize::<f64>(), entropy::<f64>(data));
+        assert_eq!(EntropyAccumulator::new().finalize::<f32>(), 0.0);
+        let distinct: Vec<u8> = (0..100).collect();
+        let mut acc = EntropyAccumulator::new();
+        acc.update(&distinct);
+        assert_eq!(acc.finalize::<f64>(), entropy::<f64>(&distinct));
+        acc.update(&[0; 1000]);
+        acc.remove(&distinct);
+        assert_eq!(acc.finalize::<f64>(), 0.0);
+    }
+    #[test]
+    fn normalized() {
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(normalized_entropy::<f64>(&all), 1.0);
+        assert_eq!(normalized_entropy::<f64>(b"AAAA"), 0.0);
+        assert_eq!(normalized_entropy::<f32>(b"ABCD"), 0.25);
+    }
+    #[test]
+    fn bases() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let bits: f64 = entropy(data);
+        assert_eq!(entropy_base(data, 2.0), bits);
+        let nats: f64 = entropy_base(data, std::f64::consts::E);
+        assert!((nats - bits * std::f64::consts::LN_2).abs() < 1e-12);
+        let dits: f64 = entropy_base(data, 10.0);
+        assert!((dits - bits * std::f64::consts::LOG10_2).abs() < 1e-12);
+        assert!(entropy_base::<f32>(data, 0.5).is_nan());
+        assert!(entropy_base::<f32>(data, f32::NAN).is_nan());
+    }
+    #[test]
+    fn base_two_matches_entropy() {
+        for data in [
+            &b""[..],
+            b"AAAA",
+            b"Hello, world!",
+            &(0..=255).collect::<Vec<u8>>(),
+            &pseudo_random(10_000, 7),
+        ] {
+            assert_eq!(entropy_base::<f64>(data, 2.0), entropy::<f64>(data));
+            assert_eq!(entropy_base::<f32>(data, 2.0), entropy::<f32>(data));
+        }
+    }
+    #[test]
+    fn renyi() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(renyi_entropy::<f64>(data, 1.0), entropy::<f64>(data));
+        let distinct = 28.0_f64;
+        assert!((renyi_entropy::<f64>(data, 0.0) - distinct.log2()).abs() < 1e-12);
+        let alphas = [
+            0.0,
+            0.5,
+            0.999,
+            1.0,
+            1.001,
+            2.0,
+            5.0,
+            100.0,
+            1000.0,
+            f64::INFINITY,
+        ];
+        let values: Vec<f64> = alphas.iter().map(|&a| renyi_entropy(data, a)).collect();
+        for pair in values.windows(2) {
+            assert!(pair[0] >= pair[1] - 1e-9);
+        }
+        let min_e = -(8.0_f64 / data.len() as f64).log2();
+        assert!((values[values.len() - 2] - min_e).abs() < 0.01);
+        assert_eq!(values[values.len() - 1], min_e);
+        assert_eq!(renyi_entropy::<f32>(b"", 2.0), 0.0);
+        assert!(renyi_entropy::<f32>(data, -1.0).is_nan());
+    }
+    #[test]
+    fn min() {
+        let samples: [&[u8]; 5] = [b"", b"A", b"AAAB", b"ABCD", b"Hello, world!"];
+        for data in samples {
+            assert!(min_entropy::<f64>(data) <= entropy::<f64>(data));
+        }
+        assert_eq!(min_entropy::<f32>(b""), 0.0);
+        assert_eq!(min_entropy::<f32>(b"AAAA"), 0.0);
+        assert_eq!(min_entropy::<f32>(b"ABCD"), 2.0);
+    }
+    #[test]
+    fn generic() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        let bytes: Vec<u8> = text.bytes().collect();
+        let e_bytes: f64 = entropy(&bytes);
+        let e_generic: f64 = entropy_generic(&bytes);
+        assert!((e_bytes - e_generic).abs() < 1e-12);
+        let words: Vec<&str> = text.split(' ').collect();
+        assert!((entropy_generic::<_, f64>(&words) - 9.0_f64.log2()).abs() < 1e-12);
+        assert_eq!(entropy_generic::<u32, f32>(&[]), 0.0);
+    }
+    #[test]
+    fn histogram() {
+        let counts = byte_histogram(b"Hello, world!");
+        assert_eq!(counts[b'l' as usize], 3);
+        assert_eq!(counts[b'o' as usize], 2);
+        assert_eq!(counts[0], 0);
+        assert_eq!(byte_histogram(b""), [0; 256]);
+    }
+    #[test]
+    fn report() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let report = analyze::<f64>(data);
+        assert_eq!(report.per_byte_entropy, entropy::<f64>(data));
+        assert_eq!(report.total_entropy, total_entropy::<f64>(data));
+        assert_eq!(report.length, data.len());
+        assert_eq!(report.distinct_bytes, 28);
+        let empty = analyze::<f32>(b"");
+        assert_eq!(empty.per_byte_entropy, 0.0);
+        assert_eq!(empty.distinct_bytes, 0);
+    }
+    #[test]
+    fn kl() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(kl_divergence::<f64>(data, data), 0.0);
+        assert_eq!(kl_divergence::<f64>(b"", data), 0.0);
+        assert!(kl_divergence::<f64>(b"AAAB", b"AB") > 0.0);
+        assert!(kl_divergence::<f64>(b"ABC", b"AB").is_infinite());
+        assert!(kl_divergence::<f64>(b"ABC", b"").is_infinite());
+        let d: f64 = kl_divergence_smoothed(b"ABC", b"AB", 1e-3);
+        assert!(d.is_finite() && d > 0.0);
+        assert_eq!(kl_divergence_smoothed::<f64>(data, data, 0.0), 0.0);
+    }
+    #[test]
+    fn cross() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        let h: f64 = cross_entropy(data, data);
+        assert!((h - entropy::<f64>(data)).abs() < 1e-12);
+        let kl: f64 = kl_divergence(b"AAAB", b"AABB");
+        let h: f64 = cross_entropy(b"AAAB", b"AABB");
+        assert!((h - (entropy::<f64>(b"AAAB") + kl)).abs() < 1e-12);
+        assert!(cross_entropy::<f32>(b"ABC", b"AB").is_infinite());
+        assert_eq!(cross_entropy::<f32>(b"", b"AB"), 0.0);
+    }
+    fn pseudo_random(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                (state >> 32) as u8
+            })
+            .collect()
+    }
+    #[test]
+    fn conditional() {
+        let random = pseudo_random(1 << 20, 0x2545F4914F6CDD1D);
+        let h: f64 = conditional_entropy(&random);
+        assert!((h - entropy::<f64>(&random)).abs() < 0.1);
+        let pattern: Vec<u8> = b"0123456789".iter().copied().cycle().take(1000).collect();
+        let h: f64 = conditional_entropy(&pattern);
+        assert!(h < 1e-9);
+        assert!(entropy::<f64>(&pattern) > 3.0);
+        assert_eq!(conditional_entropy::<f32>(b"A"), 0.0);
+        assert_eq!(conditional_entropy::<f32>(b""), 0.0);
+    }
+    #[test]
+    fn ngram() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(ngram_entropy::<f64>(data, 1), entropy::<f64>(data));
+        let pattern: Vec<u8> = b"AB".iter().copied().cycle().take(100).collect();
+        assert!((ngram_entropy::<f64>(&pattern, 2) - 1.0).abs() < 1e-3);
+        assert!((ngram_entropy_per_byte::<f64>(&pattern, 4) - 0.25).abs() < 1e-3);
+        assert_eq!(ngram_entropy::<f32>(b"AB", 3), 0.0);
+    }
+    #[test]
+    fn mutual() {
+        let x = pseudo_random(1 << 20, 0x9E3779B97F4A7C15);
+        let y = pseudo_random(1 << 20, 0xD1B54A32D192ED03);
+        let shared: f64 = mutual_information(&x, &x);
+        assert!((shared - entropy::<f64>(&x)).abs() < 1e-9);
+        let independent: f64 = mutual_information(&x, &y);
+        assert!(independent < 0.1);
+        assert_eq!(mutual_information::<f32>(b"", b""), 0.0);
+    }
+    #[test]
+    #[should_panic(expected = "equal length")]
+    fn mutual_unequal() {
+        let _: f64 = mutual_information(b"AB", b"ABC");
+    }
+    #[test]
+    fn edge_bits() {
+        let values = vec![(0, 1.0_f64), (1, 7.9), (2, 7.8), (3, 0.5)];
+        let edges = detect_edges(&values, 0.95, 0.85);
+        assert_eq!(edges.len(), 3);
+        for edge in edges {
+            assert_eq!(edge.entropy_bits, values[edge.block_index].1);
+            assert_eq!(edge.entropy, edge.entropy_bits / 8.0);
+        }
+    }
+    #[test]
+    fn dwell() {
+        let values: Vec<(usize, f64)> = [1.0, 7.9, 1.0, 7.9, 7.9, 7.9, 1.0, 1.0, 7.9]
+            .into_iter()
+            .enumerate()
+            .collect();
+        assert_eq!(
+            detect_edges_with_dwell(&values, 0.95, 0.85, 1),
+            detect_edges(&values, 0.95, 0.85)
+        );
+        assert_eq!(
+            detect_edges_with_dwell(&values, 0.95, 0.85, 0),
+            detect_edges(&values, 0.95, 0.85)
+        );
+        let edges = detect_edges_with_dwell(&values, 0.95, 0.85, 2);
+        let indices: Vec<usize> = edges.iter().map(|e| e.block_index).collect();
+        assert_eq!(indices, vec![3, 6]);
+        let edges = detect_edges_with_dwell(&values, 0.95, 0.85, 3);
+        let indices: Vec<usize> = edges.iter().map(|e| e.block_index).collect();
+        assert_eq!(indices, vec![3]);
+    }
+    #[test]
+    fn slope() {
+        let values = vec![(0, 7.6_f64), (1, 0.4), (2, 4.0), (3, 7.8)];
+        let edges = detect_edges(&values, 0.95, 0.85);
+        assert_eq!(edges.len(), 3);
+        assert_eq!(edges[0].slope, 0.0);
+        assert!((edges[1].slope - (0.4 - 7.6) / 8.0).abs() < 1e-12);
+        assert!((edges[2].slope - (7.8 - 4.0) / 8.0).abs() < 1e-12);
+    }
+    #[test]
+    fn regions() {
+        let values: Vec<(usize, f64)> = [0.4, 0.4, 7.6, 7.9, 7.8, 4.0, 0.8]
+            .into_iter()
+            .enumerate()
+            .collect();
+        let edges = detect_edges(&values, 0.95, 0.85);
+        let regions = detect_regions(&values, 0.95, 0.85);
+        assert_eq!(regions.len(), edges.len());
+        for (region, edge) in regions.iter().zip(&edges) {
+            assert_eq!(region.start_index, edge.block_index);
+        }
+        assert_eq!(regions[0].kind, RegionKind::Low);
+        assert_eq!((regions[0].start_index, regions[0].end_index), (0, 1));
+        assert!((regions[0].mean_entropy - 0.05).abs() < 1e-12);
+        assert_eq!(regions[1].kind, RegionKind::High);
+        assert_eq!((regions[1].start_index, regions[1].end_index), (2, 4));
+        assert_eq!(regions[2].kind, RegionKind::Low);
+        assert_eq!((regions[2].start_index, regions[2].end_index), (5, 6));
+        assert!(detect_regions::<f64>(&[], 0.95, 0.85).is_empty());
+    }
+    #[test]
+    fn regions_with_unordered_indices() {
+        let regions = detect_regions(&[(0, 7.9_f64), (0, 1.0), (1, 7.9)], 0.7, 0.3);
+        let spans: Vec<(usize, usize, RegionKind)> = regions
+            .iter()
+            .map(|r| (r.start_index, r.end_index, r.kind))
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                (0, 0, RegionKind::High),
+                (0, 0, RegionKind::Low),
+                (1, 1, RegionKind::High)
+            ]
+        );
+        let regions = detect_regions(&[(5, 1.0_f64), (3, 1.0), (9, 7.9), (2, 7.9)], 0.7, 0.3);
+        assert_eq!(regions.len(), 2);
+        assert_eq!((regions[0].start_index, regions[0].end_index), (5, 3));
+        assert_eq!((regions[1].start_index, regions[1].end_index), (9, 2));
+    }
+    #[test]
+    fn streaming_edges() {
+        let values: Vec<(usize, f64)> = [1.0, 7.9, 7.0, 7.9, 1.0, 7.2, 0.5]
+            .into_iter()
+            .enumerate()
+            .collect();
+        let mut detector = EdgeDetector::new(0.95, 0.85);
+        let streamed: Vec<EntropyEdge<f64>> = values
+            .iter()
+            .filter_map(|&(i, e)| detector.push(i, e))
+            .collect();
+        assert_eq!(streamed, detect_edges(&values, 0.95, 0.85));
+        assert_eq!(streamed.len(), 5);
+    }
+    #[test]
+    fn iter_edges() {
+        let values: Vec<(usize, f32)> = [1.0, 7.9, 7.0, 7.9, 1.0].into_iter().enumerate().collect();
+        let lazy: Vec<EntropyEdge<f32>> =
+            detect_edges_iter(values.iter().copied(), 0.95, 0.85).collect();
+        assert_eq!(lazy, detect_edges(&values, 0.95, 0.85));
+        assert_eq!(
+            detect_edges_iter(Vec::<(usize, f32)>::new(), 0.95, 0.85).count(),
+            0
+        );
+    }
+    #[test]
+    fn block_iterator() {
+        let data = pseudo_random(10_000, 0x853C49E6748FEA9B);
+        let blocks: Vec<(usize, f64)> = BlockEntropy::new(&data[..], 1024)
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(blocks.len(), 10);
+        for (&(i, e), chunk) in blocks.iter().zip(data.chunks(1024)) {
+            assert_eq!(e, entropy::<f64>(chunk));
+            assert_eq!(blocks[i].1, e);
+        }
+        assert_eq!(BlockEntropy::<_, f32>::new(&b""[..], 16).count(), 0);
+    }
+    #[test]
+    fn fallible() {
+        let data = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(try_entropy::<f64>(data), Ok(entropy::<f64>(data)));
+        assert_eq!(try_entropy::<f32>(b""), Ok(0.0));
+        assert_eq!(
+            EntropyError::Conversion(7).to_string(),
+            "could not convert 7 to the float type"
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_edges() {
+        let values = vec![(0, 7.8_f64), (1, 2.0)];
+        let edges = detect_edges(&values, 0.95, 0.85);
+        let json = serde_json::to_string(&edges).unwrap();
+        assert!(json.contains(r#""edge_type":"rising""#));
+        assert!(json.contains(r#""edge_type":"falling""#));
+        let parsed: Vec<EntropyEdge<f64>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, edges);
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_blocks() {
+        let data = pseudo_random(100_000, 0xA0761D6478BD642F);
+        let values = par_block_entropy::<f64>(&data, 1000);
+        assert_eq!(values.len(), 100);
+        for (&(i, e), chunk) in values.iter().zip(data.chunks(1000)) {
+            assert_eq!(e, entropy::<f64>(chunk));
+            assert_eq!(values[i].1, e);
+        }
+    }
+    #[test]
+    fn log_table() {
+        for (i, &cached) in LOG2_TABLE.iter().enumerate().skip(1) {
+            assert!((cached - (i as f64).log2()).abs() < 1e-14);
+        }
+        let samples = [
+            pseudo_random(10, 1),
+            pseudo_random(255, 2),
+            pseudo_random(100_000, 3),
+            vec![7; 1000],
+            b"Hello, world!".to_vec(),
+            Vec::new(),
+        ];
+        for data in &samples {
+            let fast: f64 = entropy_fast(data);
+            assert!((fast - entropy::<f64>(data)).abs() < 1e-12);
+            let fast: f32 = entropy_fast(data);
+            assert!((fast - entropy::<f32>(data)).abs() < 1e-4);
+        }
+    }
+    #[cfg(feature = "simd")]
+    #[test]
+    fn interleaved_histogram() {
+        for len in [0, 1, 3, 4, 5, 1023, 4096] {
+            let data = pseudo_random(len, len as u64 + 1);
+            let mut expected = [0usize; 256];
+            for byte in &data {
+                expected[*byte as usize] += 1;
+            }
+            assert_eq!(byte_histogram_interleaved(&data), expected);
+        }
+        assert_eq!(byte_histogram_interleaved(&[9; 10])[9], 10);
+    }
+    #[test]
+    fn tsallis() {
+        let data = pseudo_random(4096, 7);
+        let nats = entropy::<f64>(&data) * core::f64::consts::LN_2;
+        assert_eq!(tsallis_entropy(&data, 1.0), nats);
+        for q in [1.0 + 1e-6, 1.0 - 1e-6] {
+            assert!((tsallis_entropy(&data, q) - nats).abs() < 1e-4);
+        }
+        let s2: f64 = tsallis_entropy(b"AB", 2.0);
+        assert!((s2 - 0.5).abs() < 1e-12);
+        assert_eq!(tsallis_entropy::<f64>(&[5; 100], 3.0), 0.0);
+        assert_eq!(tsallis_entropy::<f64>(b"", 2.0), 0.0);
+        assert!(tsallis_entropy::<f64>(b"AB", f64::NAN).is_nan());
+    }
+    #[test]
+    fn hartley() {
+        assert_eq!(hartley_entropy::<f64>(b""), 0.0);
+        assert_eq!(hartley_entropy::<f64>(&[3; 50]), 0.0);
+        assert_eq!(hartley_entropy::<f64>(b"ABCD"), 2.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(hartley_entropy::<f64>(&all), 8.0);
+        for data in [
+            pseudo_random(100, 1),
+            b"Hello, world!".to_vec(),
+            vec![1, 1, 1, 2],
+        ] {
+            assert!(entropy::<f64>(&data) <= hartley_entropy::<f64>(&data) + 1e-12);
+        }
+        let data = pseudo_random(500, 2);
+        assert_eq!(hartley_entropy::<f64>(&data), renyi_entropy(&data, 0.0));
+    }
+    #[test]
+    fn compression_estimates() {
+        assert_eq!(estimated_compressed_size::<f64>(b""), 0.0);
+        assert_eq!(compression_ratio::<f64>(b""), 0.0);
+        assert_eq!(estimated_compressed_size::<f64>(&[0; 1000]), 0.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(estimated_compressed_size::<f64>(&all), 256.0);
+        assert_eq!(compression_ratio::<f64>(&all), 1.0);
+        let data = pseudo_random(1000, 4);
+        let ratio: f64 = compression_ratio(&data);
+        assert!((ratio - entropy::<f64>(&data) / 8.0).abs() < 1e-12);
+    }
+    #[test]
+    fn chi_square() {
+        assert_eq!(chi_square_uniform::<f64>(b""), 0.0);
+        let counter: Vec<u8> = (0..4096).map(|i| i as u8).collect();
+        assert_eq!(chi_square_uniform::<f64>(&counter), 0.0);
+        let random: f64 = chi_square_uniform(&pseudo_random(1 << 20, 9));
+        assert!((150.0..400.0).contains(&random));
+        let text: f64 = chi_square_uniform(&b"the quick brown fox ".repeat(100));
+        assert!(text > 10_000.0);
+    }
+    #[test]
+    fn guessing() {
+        assert_eq!(guessing_entropy::<f64>(b""), 0.0);
+        assert_eq!(guessing_entropy::<f64>(&[42; 10]), 1.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(guessing_entropy::<f64>(&all), 128.5);
+        let g: f64 = guessing_entropy(b"BABCBB");
+        assert!((g - (4.0 + 2.0 * 1.0 + 3.0 * 1.0) / 6.0).abs() < 1e-12);
+    }
+    #[test]
+    fn gradient() {
+        assert!(entropy_gradient::<f64>(&[]).is_empty());
+        assert_eq!(entropy_gradient(&[(3, 4.0)]), vec![(3, 0.0)]);
+        let values = vec![(0, 2.0), (1, 2.0), (2, 8.0), (3, 1.0)];
+        let gradient = entropy_gradient(&values);
+        assert_eq!(gradient, vec![(0, 0.0), (1, 0.0), (2, 6.0), (3, -7.0)]);
+    }
+    #[test]
+    fn smoothing() {
+        let values = vec![(0, 8.0), (1, 0.0), (2, 8.0), (3, 0.0), (4, 8.0)];
+        assert_eq!(smooth_entropy(&values, 1), values);
+        let smoothed = smooth_entropy(&values, 2);
+        assert_eq!(
+            smoothed,
+            vec![(0, 4.0), (1, 4.0), (2, 4.0), (3, 4.0), (4, 8.0)]
+        );
+        let smoothed = smooth_entropy(&values, 100);
+        assert!(smoothed.iter().all(|&(_, e)| (e - 4.8_f64).abs() < 1e-12));
+        assert!(smooth_entropy::<f64>(&[], 3).is_empty());
+        let indices: Vec<usize> = smooth_entropy(&[(5, 1.0), (9, 2.0)], 3)
+            .iter()
+            .map(|&(i, _)| i)
+            .collect();
+        assert_eq!(indices, vec![5, 9]);
+    }
+    #[test]
+    #[should_panic]
+    fn smoothing_zero_window() {
+        smooth_entropy(&[(0, 1.0_f64)], 0);
+    }
+    #[test]
+    fn otsu_thresholds() {
+        assert_eq!(auto_thresholds::<f64>(&[]), (1.0, 0.0));
+        assert_eq!(auto_thresholds(&[(0, 5.0), (1, 5.0)]), (1.0, 0.0));
+
+        let mut values = Vec::new();
+        for i in 0..100 {
+            let jitter = (i % 5) as f64 * 0.02;
+            let e = if (i / 20) % 2 == 0 {
+                2.4 + jitter
+            } else {
+                7.8 + jitter
+            };
+            values.push((i, e));
+        }
+        let (high, low) = auto_thresholds(&values);
+        assert!(low > 0.32 && low < high && high < 0.975);
+        let edges = detect_edges(&values, high, low);
+        let blocks: Vec<usize> = edges.iter().map(|e| e.block_index).collect();
+        assert_eq!(blocks, vec![0, 20, 40, 60, 80]);
+    }
+    #[test]
+    fn extreme_blocks() {
+        assert_eq!(max_entropy_block::<f64>(b"", 4), None);
+        assert_eq!(min_entropy_block::<f64>(b"", 4), None);
+        let data = [&[0u8; 64][..], &pseudo_random(64, 5), &[1u8; 64]].concat();
+        let (max_i, max_e) = max_entropy_block::<f64>(&data, 64).unwrap();
+        assert_eq!(max_i, 1);
+        assert_eq!(max_e, entropy::<f64>(&data[64..128]));
+        assert_eq!(min_entropy_block::<f64>(&data, 64), Some((0, 0.0)));
+        assert_eq!(max_entropy_block::<f64>(b"AAAAAB", 4), Some((1, 1.0)));
+    }
+    #[test]
+    fn blocks() {
+        assert!(block_entropies::<f64>(b"", 16).is_empty());
+        let data = pseudo_random(10_000, 11);
+        let values = block_entropies::<f64>(&data, 1024);
+        let streamed: Vec<(usize, f64)> = BlockEntropy::new(&data[..], 1024)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(values.len(), 10);
+        assert_eq!(values, streamed);
+    }
+    #[test]
+    fn bits() {
+        assert_eq!(bit_entropy::<f64>(b""), 0.0);
+        assert_eq!(bit_entropy::<f64>(&[0; 100]), 0.0);
+        assert_eq!(bit_entropy::<f64>(&[0xFF; 100]), 0.0);
+        assert_eq!(bit_entropy::<f64>(&[0x55; 100]), 1.0);
+        assert_eq!(entropy::<f64>(&[0x55; 100]), 0.0);
+        let e: f64 = bit_entropy(&[0x01; 10]);
+        let expected = -(0.125_f64 * 0.125_f64.log2() + 0.875 * 0.875_f64.log2());
+        assert!((e - expected).abs() < 1e-12);
+    }
+    #[test]
+    fn filtered() {
+        let data = b"Hello, world!\0\0\0\0";
+        assert_eq!(
+            entropy_filtered::<f64>(data, |byte| byte != 0),
+            entropy::<f64>(b"Hello, world!")
+        );
+        assert_eq!(
+            entropy_filtered::<f64>(data, |_| true),
+            entropy::<f64>(data)
+        );
+        assert_eq!(entropy_filtered::<f64>(data, |_| false), 0.0);
+        let e: f64 = entropy_filtered(data, |byte| byte.is_ascii_alphabetic());
+        assert_eq!(e, entropy::<f64>(b"Helloworld"));
+    }
+    #[test]
+    fn accumulator_remove() {
+        let data = pseudo_random(2000, 12);
+        let window = 256;
+        let mut acc = EntropyAccumulator::new();
+        acc.update(&data[..window]);
+        for start in 1..=data.len() - window {
+            acc.update(&data[start + window - 1..start + window]);
+            acc.remove(&data[start - 1..start]);
+            assert_eq!(acc.len(), window as u64);
+            let expected = entropy::<f64>(&data[start..start + window]);
+            assert!((acc.finalize::<f64>() - expected).abs() < 1e-12);
+        }
+        acc.remove(&data[data.len() - window..]);
+        assert!(acc.is_empty());
+        assert_eq!(acc.counts(), &[0; 256]);
+    }
+    #[test]
+    #[should_panic]
+    fn accumulator_remove_unseen() {
+        let mut acc = EntropyAccumulator::new();
+        acc.update(b"AB");
+        acc.remove(b"C");
+    }
+    #[test]
+    fn accumulator_remove_unseen_is_unchanged() {
+        let mut acc = EntropyAccumulator::new();
+        acc.update(b"AB");
+        let before = acc.clone();
+        let removed = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            acc.remove(b"AC");
+        }));
+        assert!(removed.is_err());
+        assert_eq!(acc, before);
+    }
+    #[test]
+    fn accumulator_merge() {
+        let data = pseudo_random(10_000, 13);
+        let parts: Vec<EntropyAccumulator> = data
+            .chunks(3000)
+            .map(|chunk| {
+                let mut acc = EntropyAccumulator::new();
+                acc.update(chunk);
+                acc
+            })
+            .collect();
+        let mut merged = EntropyAccumulator::new();
+        for part in parts.iter().rev() {
+            merged.merge(part);
+        }
+        let mut whole = EntropyAccumulator::new();
+        whole.update(&data);
+        assert_eq!(merged.counts(), whole.counts());
+        assert_eq!(merged.len(), whole.len());
+        assert_eq!(merged.finalize::<f64>(), whole.finalize::<f64>());
+
+        let mut summed = parts[0].clone() + parts[1].clone();
+        summed += &(parts[2].clone() + parts[3].clone());
+        assert_eq!(summed.counts(), whole.counts());
+    }
+    #[test]
+    fn distinct() {
+        assert_eq!(distinct_bytes(b""), 0);
+        assert_eq!(distinct_bytes(&[7; 100]), 1);
+        let all: Vec<u8> = (0..=255).rev().collect();
+        assert_eq!(distinct_bytes(&all), 256);
+        let data = b"the quick brown fox";
+        assert_eq!(distinct_bytes(data), analyze::<f64>(data).distinct_bytes);
+    }
+    #[test]
+    fn most_frequent() {
+        assert!(top_bytes(b"", 5).is_empty());
+        assert!(top_bytes(b"abc", 0).is_empty());
+        assert_eq!(
+            top_bytes(b"cbacba", 10),
+            vec![(b'a', 2), (b'b', 2), (b'c', 2)]
+        );
+        let mut data = vec![0u8; 90];
+        data.extend_from_slice(&[0xFF; 10]);
+        assert_eq!(top_bytes(&data, 1), vec![(0, 90)]);
+        assert_eq!(top_bytes(&data, 3), vec![(0, 90), (0xFF, 10)]);
+    }
+    #[test]
+    fn alphabet_normalization() {
+        let data = pseudo_random(5000, 14);
+        assert_eq!(
+            normalized_entropy_for_alphabet::<f64>(&data, 256),
+            normalized_entropy::<f64>(&data)
+        );
+        assert_eq!(
+            normalized_entropy_for_alphabet::<f64>(b"0123456789abcdef", 16),
+            1.0
+        );
+        assert_eq!(normalized_entropy_for_alphabet::<f64>(b"AACC", 4), 0.5);
+        assert_eq!(normalized_entropy_for_alphabet::<f64>(b"AB", 1), 0.0);
+        assert_eq!(normalized_entropy_for_alphabet::<f64>(b"AB", 0), 0.0);
+        assert!(normalized_entropy_for_alphabet::<f64>(b"ABCD", 2) > 1.0);
+    }
+    #[test]
+    fn edges_normalized() {
+        let values: Vec<(usize, f64)> = pseudo_random(200, 15)
+            .chunks(10)
+            .map(entropy)
+            .enumerate()
+            .collect();
+        assert_eq!(
+            detect_edges_normalized(&values, 0.95, 0.85, 8.0),
+            detect_edges(&values, 0.95, 0.85)
+        );
+        let hex = vec![(0, 3.9), (1, 1.0), (2, 3.95)];
+        let edges = detect_edges_normalized(&hex, 0.95, 0.85, 4.0);
+        let types: Vec<EdgeType> = edges.iter().map(|e| e.edge_type).collect();
+        assert_eq!(
+            types,
+            vec![EdgeType::Rising, EdgeType::Falling, EdgeType::Rising]
+        );
+        assert_eq!(edges[0].entropy_bits, 3.9);
+        assert!(
+            detect_edges(&hex, 0.95, 0.85)
+                .iter()
+                .all(|e| e.edge_type == EdgeType::Falling)
+        );
+    }
+    #[test]
+    fn single_type_edges() {
+        let values: Vec<(usize, f64)> = (0..50)
+            .map(|i| (i, if (i / 7) % 2 == 0 { 7.9 } else { 1.5 }))
+            .collect();
+        let all = detect_edges(&values, 0.95, 0.85);
+        let rising = detect_rising_edges(&values, 0.95, 0.85);
+        let falling = detect_falling_edges(&values, 0.95, 0.85);
+        assert!(!rising.is_empty() && !falling.is_empty());
+        assert_eq!(rising.len() + falling.len(), all.len());
+        assert!(rising.iter().all(|e| e.edge_type == EdgeType::Rising));
+        assert!(falling.iter().all(|e| e.edge_type == EdgeType::Falling));
+        let expected: Vec<_> = all
+            .iter()
+            .copied()
+            .filter(|e| e.edge_type == EdgeType::Rising)
+            .collect();
+        assert_eq!(rising, expected);
+    }
+    #[test]
+    fn classify() {
+        assert_eq!(classify_data(b""), DataClass::Unknown);
+        assert_eq!(classify_data(b"Hello, world!\n"), DataClass::Text);
+        assert_eq!(
+            classify_data(&pseudo_random(1 << 16, 16)),
+            DataClass::Encrypted
+        );
+        // High entropy but with one byte value heavily over-represented.
+        let mut skewed = pseudo_random(1 << 16, 17);
+        for byte in skewed.iter_mut().step_by(64) {
+            *byte = 0x78;
+        }
+        assert_eq!(classify_data(&skewed), DataClass::Compressed);
+        // Random code-like bytes padded with zeros.
+        let mut code = pseudo_random(1 << 16, 18);
+        for (i, byte) in code.iter_mut().enumerate() {
+            if i % 4 == 0 {
+                *byte = 0;
+            } else if i % 4 == 1 {
+                *byte &= 0x0F;
+            }
+        }
+        assert_eq!(classify_data(&code), DataClass::Executable);
+        assert_eq!(classify_data(&[0; 100]), DataClass::Unknown);
+    }
+    #[test]
+    fn xor() {
+        let key = pseudo_random(4096, 19);
+        let m1 = b"attack at dawn ".repeat(273);
+        let m2 = b"retreat at dusk".repeat(273);
+        let c1: Vec<u8> = m1.iter().zip(&key).map(|(m, k)| m ^ k).collect();
+        let c2: Vec<u8> = m2.iter().zip(&key).map(|(m, k)| m ^ k).collect();
+        let reused: f64 = xor_entropy(&c1, &c2);
+        let independent: f64 = xor_entropy(&c1, &pseudo_random(4096, 20));
+        assert!(reused < 4.0);
+        assert!(independent > 7.9);
+        assert_eq!(xor_entropy::<f64>(&c1, &[]), 0.0);
+        assert_eq!(
+            xor_entropy::<f64>(&c1[..10], &c2),
+            xor_entropy::<f64>(&c1[..10], &c2[..10])
+        );
+    }
+    #[test]
+    fn positional() {
+        assert_eq!(positional_entropy::<f64>(b"abc", 4), vec![0.0; 4]);
+        let random = pseudo_random(4000, 21);
+        let mut data = Vec::new();
+        for chunk in random.chunks(4) {
+            data.extend_from_slice(b"HDR");
+            data.push(chunk[0]);
+        }
+        data.extend_from_slice(b"HD");
+        let e = positional_entropy::<f64>(&data, 4);
+        assert_eq!(&e[..3], &[0.0, 0.0, 0.0]);
+        assert!(e[3] > 7.5);
+    }
+    #[test]
+    #[should_panic]
+    fn positional_zero_record() {
+        positional_entropy::<f64>(b"abc", 0);
+    }
+    #[test]
+    fn apen() {
+        let counter: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        let random = pseudo_random(1024, 22);
+        let periodic: f64 = approximate_entropy(&counter, 2, 0.0);
+        let noisy: f64 = approximate_entropy(&random, 2, 32.0);
+        assert_eq!(entropy::<f64>(&counter), 8.0);
+        assert!(periodic.abs() < 0.01);
+        assert!(noisy > 0.5);
+        assert_eq!(approximate_entropy::<f64>(b"AB", 2, 1.0), 0.0);
+        assert!(approximate_entropy::<f64>(&[9; 100], 2, 0.0).abs() < 1e-12);
+    }
+    #[test]
+    fn sampen() {
+        let counter: Vec<u8> = (0..1024).map(|i| i as u8).collect();
+        let random = pseudo_random(1024, 23);
+        let periodic: f64 = sample_entropy(&counter, 2, 0.0);
+        let noisy: f64 = sample_entropy(&random, 2, 32.0);
+        assert_eq!(periodic, 0.0);
+        assert!(noisy > 0.5);
+        // Every pair of 2 byte patterns matches, and so does every extension.
+        assert_eq!(sample_entropy::<f64>(&[9; 50], 2, 0.0), 0.0);
+        assert!(sample_entropy::<f64>(b"AB", 2, 1.0).is_infinite());
+        assert!(sample_entropy::<f64>(b"AAAB", 1, 0.0).is_finite());
+    }
+    #[test]
+    fn changepoints() {
+        assert!(detect_changepoints::<f64>(&[], 1.0).is_empty());
+        let noise = pseudo_random(90, 24);
+        let values: Vec<(usize, f64)> = (0..90)
+            .map(|i| {
+                let level = [2.0, 7.5, 5.0][i / 30];
+                (i * 2, level + (noise[i] as f64 / 255.0 - 0.5) * 0.2)
+            })
+            .collect();
+        assert_eq!(detect_changepoints(&values, 1.0), vec![60, 120]);
+        let flat: Vec<(usize, f64)> = (0..50).map(|i| (i, 6.0)).collect();
+        assert!(detect_changepoints(&flat, 0.1).is_empty());
+    }
+    #[test]
+    #[should_panic]
+    fn changepoints_zero_sensitivity() {
+        detect_changepoints(&[(0, 1.0_f64)], 0.0);
+    }
+    #[test]
+    fn from_reader() {
+        let data = pseudo_random(200_000, 25);
+        let e: f64 = entropy_from_reader(&data[..]).unwrap();
+        assert!((e - entropy::<f64>(&data)).abs() < 1e-12);
+        assert_eq!(entropy_from_reader::<_, f64>(io::empty()).unwrap(), 0.0);
+
+        struct Failing;
+        impl Read for Failing {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+        assert!(entropy_from_reader::<_, f64>(Failing).is_err());
+    }
+    #[test]
+    fn from_reader_progress() {
+        let data = pseudo_random(200_000, 45);
+        let mut reports = Vec::new();
+        let e: f64 =
+            entropy_from_reader_with_progress(&data[..], |bytes| reports.push(bytes)).unwrap();
+        assert_eq!(e, entropy_from_reader::<_, f64>(&data[..]).unwrap());
+        assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(reports.last(), Some(&200_000));
+    }
+    #[test]
+    fn edge_builder() {
+        let values: Vec<(usize, f64)> = pseudo_random(400, 26)
+            .chunks(8)
+            .map(entropy)
+            .enumerate()
+            .map(|(i, e)| (i, if i % 9 < 4 { e } else { e / 3.0 }))
+            .collect();
+        let builder = EdgeDetectorBuilder::new();
+        assert_eq!(builder.detect(&values), detect_edges(&values, 0.95, 0.85));
+        let builder = builder.high_threshold(0.3).low_threshold(0.2);
+        assert_eq!(builder.detect(&values), detect_edges(&values, 0.3, 0.2));
+        assert_eq!(
+            builder.min_dwell(3).detect(&values),
+            detect_edges_with_dwell(&values, 0.3, 0.2, 3)
+        );
+        assert_eq!(
+            builder.max_entropy(4.0).detect(&values),
+            detect_edges_normalized(&values, 0.3, 0.2, 4.0)
+        );
+        assert_eq!(
+            builder.only(EdgeType::Falling).detect(&values),
+            detect_falling_edges(&values, 0.3, 0.2)
+        );
+        assert!(!builder.detect(&values).is_empty());
+    }
+    #[test]
+    fn weighted() {
+        let data = pseudo_random(3000, 27);
+        assert_eq!(weighted_entropy(&data, &[1.0; 256]), entropy::<f64>(&data));
+        assert_eq!(weighted_entropy(&data, &[0.0; 256]), 0.0);
+        let doubled: f64 = weighted_entropy(&data, &[2.0; 256]);
+        assert!((doubled - 2.0 * entropy::<f64>(&data)).abs() < 1e-12);
+        assert_eq!(weighted_entropy::<f64>(b"", &[1.0; 256]), 0.0);
+    }
+    #[test]
+    fn from_counts() {
+        assert_eq!(entropy_from_counts::<f64>(&[0; 256]), 0.0);
+        let a = pseudo_random(1000, 28);
+        let b = b"Hello, world!".repeat(20);
+        assert_eq!(
+            entropy_from_counts::<f64>(&byte_histogram(&a)),
+            entropy::<f64>(&a)
+        );
+        let mut counts = byte_histogram(&a);
+        for (count, other) in counts.iter_mut().zip(byte_histogram(&b)) {
+            *count += other;
+        }
+        let whole = [a, b].concat();
+        assert_eq!(entropy_from_counts::<f64>(&counts), entropy::<f64>(&whole));
+    }
+    #[test]
+    fn distance() {
+        let a = vec![(0, 1.0_f64), (1, 5.0), (2, 8.0)];
+        assert_eq!(series_distance(&a, &a), 0.0);
+        assert_eq!(series_distance(&a, &[]), 0.0);
+        let b = vec![(0, 2.0), (1, 3.0), (2, 8.0), (3, 0.0)];
+        assert_eq!(series_distance(&a, &b), 1.0);
+        assert_eq!(series_distance(&a, &b), series_distance(&b, &a));
+    }
+    #[test]
+    fn autocorrelation() {
+        assert_eq!(entropy_autocorrelation::<f64>(&[], 2), vec![0.0; 3]);
+        let flat: Vec<(usize, f64)> = (0..10).map(|i| (i, 4.0)).collect();
+        assert_eq!(entropy_autocorrelation(&flat, 2), vec![0.0; 3]);
+        let records: Vec<(usize, f64)> = (0..100)
+            .map(|i| (i, if i % 5 == 0 { 1.0 } else { 7.5 }))
+            .collect();
+        let r = entropy_autocorrelation(&records, 10);
+        assert_eq!(r.len(), 11);
+        assert_eq!(r[0], 1.0);
+        let peak = (1..=10).max_by(|&a, &b| r[a].partial_cmp(&r[b]).unwrap());
+        assert_eq!(peak, Some(5));
+        assert_eq!(entropy_autocorrelation(&records[..3], 5)[3..], [0.0; 3]);
+    }
+    #[test]
+    fn window_callback() {
+        let data = pseudo_random(1000, 29);
+        let mut seen = Vec::new();
+        for_each_window(&data, 64, 7, |offset, e: f64| seen.push((offset, e)));
+        assert_eq!(seen, windowed_entropy::<f64>(&data, 64, 7));
+        let mut calls = 0;
+        for_each_window(&data[..10], 64, 1, |_, _: f64| calls += 1);
+        assert_eq!(calls, 0);
+    }
+    #[test]
+    fn kahan() {
+        let data = pseudo_random(10_000, 30);
+        assert!((entropy_kahan::<f64>(&data) - entropy::<f64>(&data)).abs() < 1e-12);
+        assert_eq!(entropy_kahan::<f32>(b""), 0.0);
+
+        // One dominant byte with a long tail of rare ones, as in a huge,
+        // mostly empty file.
+        let mut naive_error = 0.0;
+        let mut kahan_error = 0.0;
+        for seed in 1..20 {
+            let tail = pseudo_random(255, seed);
+            let mut counts = [0usize; 256];
+            counts[0] = 4_000_000_000;
+            for (count, &t) in counts[1..].iter_mut().zip(&tail) {
+                *count = 1 + t as usize * 1000;
+            }
+            let len = counts.iter().sum();
+            let exact = histogram_entropy::<f64>(&counts, len);
+            naive_error += (histogram_entropy::<f32>(&counts, len) as f64 - exact).abs();
+            kahan_error += (histogram_entropy_kahan::<f32>(&counts, len) as f64 - exact).abs();
+        }
+        assert!(kahan_error < naive_error);
+    }
+    #[test]
+    fn stats() {
+        let empty = entropy_stats::<f64>(b"", 4);
+        assert_eq!((empty.min, empty.max, empty.std_dev), (0.0, 0.0, 0.0));
+        let values = vec![(0, 4.0_f64), (1, 2.0), (2, 6.0)];
+        let s = EntropyStats::from_values(&values);
+        assert_eq!((s.min, s.max, s.mean, s.median), (2.0, 6.0, 4.0, 4.0));
+        assert!((s.std_dev - (8.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+        let data = pseudo_random(4096, 31);
+        let s = entropy_stats::<f64>(&data, 256);
+        assert!(s.min <= s.median && s.median <= s.max);
+        assert_eq!(s, EntropyStats::from_values(&block_entropies(&data, 256)));
+    }
+    #[test]
+    fn stats_with_nan() {
+        for values in [
+            vec![(0, f64::NAN), (1, 2.0), (2, 6.0)],
+            vec![(0, 2.0), (1, 6.0), (2, f64::NAN)],
+        ] {
+            let s = EntropyStats::from_values(&values);
+            assert_eq!((s.min, s.median), (2.0, 6.0));
+            assert!(s.max.is_nan() && s.mean.is_nan() && s.std_dev.is_nan());
+        }
+        let s = EntropyStats::from_values(&[(0, f64::NAN), (1, 1.0), (2, f64::NAN)]);
+        assert_eq!(s.min, 1.0);
+        assert!(s.median.is_nan());
+    }
+    #[test]
+    fn first_above() {
+        assert_eq!(first_block_above::<f64>(b"", 4, 0.0), None);
+        let data = [&[0u8; 1000][..], &pseudo_random(500, 32), &[0u8; 100]].concat();
+        let (index, e) = first_block_above::<f64>(&data, 100, 6.0).unwrap();
+        assert_eq!(index, 10);
+        assert_eq!(e, entropy::<f64>(&data[1000..1100]));
+        assert_eq!(first_block_above::<f64>(&[1; 64], 8, 0.0), None);
+    }
+    #[test]
+    fn batch() {
+        assert!(entropy_batch::<f64>(&[]).is_empty());
+        let data = pseudo_random(10_000, 33);
+        let slices: Vec<&[u8]> = data.chunks(37).collect();
+        let e = entropy_batch::<f64>(&slices);
+        assert_eq!(e.len(), slices.len());
+        for (value, slice) in e.iter().zip(&slices) {
+            assert_eq!(*value, entropy::<f64>(slice));
+        }
+    }
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_batch() {
+        let data = pseudo_random(100_000, 34);
+        let slices: Vec<&[u8]> = data.chunks(100).collect();
+        assert_eq!(
+            par_entropy_batch::<f64>(&slices),
+            entropy_batch::<f64>(&slices)
+        );
+    }
+    #[test]
+    fn with_counts() {
+        let data = pseudo_random(5000, 35);
+        let (e, counts) = entropy_with_counts::<f64>(&data);
+        assert_eq!(e, entropy::<f64>(&data));
+        assert_eq!(counts, byte_histogram(&data));
+        assert_eq!(entropy_with_counts::<f64>(b""), (0.0, [0; 256]));
+    }
+    #[test]
+    fn redundancy_complements_normalized() {
+        let data = pseudo_random(4000, 36);
+        let r: f64 = redundancy(&data);
+        assert!((r + normalized_entropy::<f64>(&data) - 1.0).abs() < 1e-12);
+        assert_eq!(redundancy::<f64>(&[7; 100]), 1.0);
+        assert_eq!(redundancy::<f64>(b""), 1.0);
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(redundancy::<f64>(&all), 0.0);
+    }
+    #[test]
+    fn chars() {
+        let text = "The quick brown fox";
+        assert!((char_entropy::<f64>(text) - entropy::<f64>(text.as_bytes())).abs() < 1e-12);
+        let greek = "αβγδ";
+        assert_eq!(char_entropy::<f64>(greek), 2.0);
+        assert_eq!(char_entropy::<f64>("éééé"), 0.0);
+        assert_eq!(entropy::<f64>("éééé".as_bytes()), 1.0);
+        assert_eq!(char_entropy::<f64>(""), 0.0);
+    }
+    #[test]
+    fn words() {
+        assert_eq!(word_entropy::<f64>("a b c d"), 2.0);
+        assert_eq!(word_entropy::<f64>("  spam\tspam\n spam "), 0.0);
+        assert_eq!(word_entropy::<f64>("The the"), 1.0);
+        assert_eq!(word_entropy::<f64>(" \n"), 0.0);
+    }
+    #[test]
+    fn adaptive_edges() {
+        // A baseline drifting from 2 to 4 bits, then a jump to 6 bits
+        let mut values: Vec<(usize, f64)> = (0..20).map(|i| (i, 2.0 + i as f64 * 0.1)).collect();
+        values.extend((20..30).map(|i| (i, 6.0)));
+        values.extend((30..40).map(|i| (i, 2.0)));
+        assert!(
+            detect_edges(&values, 0.95, 0.85)
+                .iter()
+                .all(|e| e.edge_type == EdgeType::Falling)
+        );
+        let edges = detect_edges_adaptive(&values, 0.15, 0.15, 4);
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].block_index, 20);
+        assert_eq!(edges[0].edge_type, EdgeType::Rising);
+        assert!((edges[0].slope - 0.2625).abs() < 1e-12);
+        assert_eq!(edges[1].block_index, 30);
+        assert_eq!(edges[1].edge_type, EdgeType::Falling);
+        assert!(detect_edges_adaptive::<f64>(&values[..1], 0.0, 0.0, 4).is_empty());
+        assert!(detect_edges_adaptive::<f64>(&[], 0.1, 0.1, 4).is_empty());
+    }
+    #[test]
+    #[should_panic]
+    fn adaptive_edges_zero_window() {
+        detect_edges_adaptive::<f64>(&[(0, 1.0)], 0.1, 0.1, 0);
+    }
+    #[test]
+    fn edge_byte_offset() {
+        let edge = EntropyEdge {
+            block_index: 3,
+            edge_type: EdgeType::Rising,
+            entropy: 1.0_f64,
+            entropy_bits: 8.0,
+            slope: 0.0,
+        };
+        assert_eq!(edge.byte_offset(512), 1536);
+        assert_eq!(edge.byte_offset(0), 0);
+        assert_eq!(edge.byte_offset(usize::MAX), usize::MAX);
+    }
+    #[test]
+    fn delta_and_change() {
+        let low = vec![0u8; 4096];
+        let high = pseudo_random(4096, 37);
+        let e_high: f64 = entropy(&high);
+        assert_eq!(entropy_delta::<f64>(&low, &high), e_high);
+        assert_eq!(entropy_delta::<f64>(&high, &low), e_high);
+        let fell = entropy_change::<f64>(&high, &low, 0.1);
+        assert_eq!(fell.trend, EntropyTrend::Fell);
+        assert_eq!(fell.delta, -e_high);
+        assert_eq!((fell.previous, fell.current), (e_high, 0.0));
+        assert_eq!(
+            entropy_change::<f64>(&high, &high, 0.0).trend,
+            EntropyTrend::Stable
+        );
+        assert_eq!(
+            entropy_change::<f64>(&low, &high, 8.0).trend,
+            EntropyTrend::Stable
+        );
+    }
+    #[test]
+    fn segments() {
+        let mut data = pseudo_random(1024, 38);
+        data.extend(vec![b'A'; 1024]);
+        data.extend(pseudo_random(512, 39));
+        let segments = segment_at_edges(&data, 256, 0.8_f64, 0.5);
+        let lengths: Vec<usize> = segments.iter().map(|s| s.len()).collect();
+        assert_eq!(lengths, vec![1024, 1024, 512]);
+        assert_eq!(segments.concat(), data);
+        assert_eq!(
+            segment_at_edges(&[0u8; 10], 4, 0.8_f64, 0.5),
+            vec![&[0u8; 10][..]]
+        );
+        assert!(segment_at_edges(&[], 4, 0.8_f64, 0.5).is_empty());
+    }
+    #[test]
+    fn mapped() {
+        let data = pseudo_random(4096, 40);
+        assert_eq!(
+            entropy_mapped::<f64>(&data, |byte| byte),
+            entropy::<f64>(&data)
+        );
+        assert_eq!(entropy_mapped::<f64>(&data, |_| 0), 0.0);
+        let high_bit: f64 = entropy_mapped(&data, |byte| byte >> 7);
+        assert!(high_bit > 0.99 && high_bit <= 1.0);
+        assert_eq!(entropy_mapped::<f64>(b"", |byte| byte), 0.0);
+    }
+    #[test]
+    fn nibbles() {
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(nibble_entropy::<f64>(&all), 4.0);
+        // Uniform over 16 bytes, but every high nibble is 0
+        let low: Vec<u8> = (0..16).collect();
+        assert_eq!(entropy::<f64>(&low), 4.0);
+        let e: f64 = nibble_entropy(&low);
+        let expected =
+            -(17.0 / 32.0 * (17.0_f64 / 32.0).log2()) - 15.0 / 32.0 * (1.0_f64 / 32.0).log2();
+        assert!((e - expected).abs() < 1e-12);
+        assert_eq!(nibble_entropy::<f64>(&[0xAA; 10]), 0.0);
+        assert_eq!(nibble_entropy::<f64>(b""), 0.0);
+    }
+    #[test]
+    fn sorted_edges() {
+        let edge = |block_index, edge_type, entropy| EntropyEdge {
+            block_index,
+            edge_type,
+            entropy,
+            entropy_bits: entropy * 8.0,
+            slope: 0.0_f64,
+        };
+        let mut edges = vec![
+            edge(5, EdgeType::Falling, 0.1),
+            edge(2, EdgeType::Falling, 0.2),
+            edge(2, EdgeType::Rising, 0.99),
+            edge(2, EdgeType::Rising, 0.97),
+            edge(0, EdgeType::Rising, 0.96),
+            edge(5, EdgeType::Falling, 0.1),
+        ];
+        sort_edges(&mut edges);
+        edges.dedup();
+        let order: Vec<(usize, EdgeType, f64)> = edges
+            .iter()
+            .map(|e| (e.block_index, e.edge_type, e.entropy))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                (0, EdgeType::Rising, 0.96),
+                (2, EdgeType::Rising, 0.97),
+                (2, EdgeType::Rising, 0.99),
+                (2, EdgeType::Falling, 0.2),
+                (5, EdgeType::Falling, 0.1),
+            ]
+        );
+        assert!(EdgeType::Rising < EdgeType::Falling);
+    }
+    #[test]
+    fn sorted_edges_with_nan() {
+        let entropies = pseudo_random(200, 50);
+        let mut edges: Vec<EntropyEdge<f64>> = entropies
+            .iter()
+            .enumerate()
+            .map(|(i, &byte)| EntropyEdge {
+                block_index: i % 4,
+                edge_type: EdgeType::Rising,
+                entropy: if byte % 3 == 0 {
+                    f64::NAN
+                } else {
+                    byte as f64 / 255.0
+                },
+                entropy_bits: 0.0,
+                slope: i as f64,
+            })
+            .collect();
+        sort_edges(&mut edges);
+        for pair in edges.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            assert!(a.block_index <= b.block_index);
+            if a.block_index == b.block_index {
+                assert!(!a.entropy.is_nan() || b.entropy.is_nan());
+                if !b.entropy.is_nan() {
+                    assert!(a.entropy <= b.entropy);
+                }
+                if a.entropy.is_nan() && b.entropy.is_nan() {
+                    // Original order, recorded in the slope
+                    assert!(a.slope < b.slope);
+                }
+            }
+        }
+    }
+    #[test]
+    fn display() {
+        let edge = EntropyEdge {
+            block_index: 42,
+            edge_type: EdgeType::Rising,
+            entropy: 0.97_f64,
+            entropy_bits: 7.76,
+            slope: 0.1,
+        };
+        assert_eq!(edge.to_string(), "Rising edge at block 42 (entropy 0.97)");
+        assert_eq!(
+            format!("{edge:.3}"),
+            "Rising edge at block 42 (entropy 0.970)"
+        );
+        assert_eq!(format!("{:<8}|", EdgeType::Falling), "Falling |");
+    }
+    #[test]
+    fn percent() {
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(entropy_percent::<f64>(&all), 100.0);
+        assert_eq!(entropy_percent::<f64>(&[1; 50]), 0.0);
+        let data = pseudo_random(2000, 41);
+        let e: f64 = entropy_percent(&data);
+        assert!((e - normalized_entropy::<f64>(&data) * 100.0).abs() < 1e-9);
+        assert_eq!(percent_of_max_entropy(entropy::<f64>(&data)), e);
+    }
+    #[test]
+    fn degenerate_fast_paths() {
+        assert_eq!(entropy::<f64>(&vec![0u8; 100_000]), 0.0);
+        assert_eq!(entropy_with_counts::<f64>(&[9; 300]).1[9], 300);
+        for len in [4095, 4096, 4097, 10_000] {
+            let mut data = vec![0u8; len];
+            data[len - 1] = 1;
+            let (e, counts) = entropy_with_counts::<f64>(&data);
+            assert_eq!(counts, byte_histogram(&data));
+            assert_eq!(e, entropy_from_counts::<f64>(&counts));
+        }
+        for len in [1, 3, 5, 100, 255, 256] {
+            let distinct: Vec<u8> = (0..len).map(|i| (i * 7 % 256) as u8).collect();
+            let p = 1.0 / len as f64;
+            let general = -(len as f64) * p * p.log2();
+            assert!((entropy::<f64>(&distinct) - general).abs() < 1e-12);
+        }
+    }
+    #[test]
+    fn reverse_edges() {
+        let values = vec![
+            (0, 0.5_f64),
+            (1, 7.9),
+            (2, 7.8),
+            (3, 7.9),
+            (4, 1.0),
+            (5, 1.0),
+        ];
+        let forward = detect_edges(&values, 0.95, 0.85);
+        assert_eq!(forward.last().unwrap().block_index, 4);
+        let edges = detect_edges_reverse(&values, 0.95, 0.85);
+        let found: Vec<(usize, EdgeType)> =
+            edges.iter().map(|e| (e.block_index, e.edge_type)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (5, EdgeType::Falling),
+                (3, EdgeType::Rising),
+                (0, EdgeType::Falling)
+            ]
+        );
+        assert!((edges[1].slope - (7.9 - 1.0) / 8.0).abs() < 1e-12);
+        assert!(detect_edges_reverse::<f64>(&[], 0.95, 0.85).is_empty());
+    }
+    #[test]
+    fn cumulative() {
+        let mut data = pseudo_random(1000, 42);
+        data.extend(vec![0u8; 1500]);
+        let forward: Vec<(usize, f64)> = cumulative_entropy(&data, 300);
+        let backward: Vec<(usize, f64)> = cumulative_entropy_reverse(&data, 300);
+        assert_eq!(forward.len(), 9);
+        assert_eq!(backward.len(), 9);
+        for (i, (&(fi, f), &(bi, b))) in forward.iter().zip(&backward).enumerate() {
+            assert_eq!((fi, bi), (i, i));
+            let end = ((i + 1) * 300).min(data.len());
+            assert!((f - entropy::<f64>(&data[..end])).abs() < 1e-9);
+            assert!((b - entropy::<f64>(&data[i * 300..])).abs() < 1e-9);
+        }
+        assert_eq!(forward[8].1, backward[0].1);
+        assert_eq!(backward[8].1, 0.0);
+        assert!(cumulative_entropy::<f64>(b"", 4).is_empty());
+    }
+    #[test]
+    fn wrapped() {
+        let buffer = pseudo_random(3000, 43);
+        for start in [0, 1, 1234, 3000] {
+            let mut unwrapped = buffer[start..].to_vec();
+            unwrapped.extend_from_slice(&buffer[..start]);
+            let e: f64 = entropy_wrapped(&buffer, start);
+            assert!((e - entropy::<f64>(&unwrapped)).abs() < 1e-12);
+        }
+        assert_eq!(entropy_wrapped::<f64>(b"", 0), 0.0);
+    }
+    #[test]
+    #[should_panic]
+    fn wrapped_start_out_of_range() {
+        entropy_wrapped::<f64>(b"abc", 4);
+    }
+    #[test]
+    fn excluding() {
+        let content = pseudo_random(1000, 44);
+        let content: Vec<u8> = content.into_iter().filter(|&byte| byte != 0).collect();
+        let mut padded = content.clone();
+        padded.extend(vec![0u8; 10_000]);
+        let e: f64 = entropy_excluding(&padded, 0);
+        assert_eq!(e, entropy::<f64>(&content));
+        assert!(entropy::<f64>(&padded) < e);
+        assert_eq!(entropy_excluding::<f64>(&[0xff; 64], 0xff), 0.0);
+        assert_eq!(entropy_excluding::<f64>(b"", 0), 0.0);
+    }
+    #[test]
+    fn smoothed() {
+        let data = pseudo_random(300, 46);
+        assert_eq!(entropy_smoothed::<f64>(&data, 0.0), entropy::<f64>(&data));
+        let e: f64 = entropy_smoothed(&data, 1.0);
+        assert!(e > entropy::<f64>(&data) && e < 8.0);
+        assert!(entropy_smoothed::<f64>(&data, 1e9) > 7.999);
+        assert!((entropy_smoothed::<f64>(b"", 0.5) - 8.0).abs() < 1e-12);
+        assert_eq!(entropy_smoothed::<f64>(b"", 0.0), 0.0);
+    }
+    #[test]
+    fn miller_madow() {
+        let small = pseudo_random(64, 47);
+        let k = distinct_bytes(&small) as f64;
+        let e: f64 = entropy_miller_madow(&small);
+        assert!(e > entropy::<f64>(&small));
+        let correction = (k - 1.0) / (2.0 * 64.0 * core::f64::consts::LN_2);
+        assert!((e - entropy::<f64>(&small) - correction).abs() < 1e-12);
+        let large = pseudo_random(1_000_000, 48);
+        assert!(entropy_miller_madow::<f64>(&large) - entropy::<f64>(&large) < 1e-3);
+        assert_eq!(entropy_miller_madow::<f64>(&[3; 10]), 0.0);
+        assert_eq!(entropy_miller_madow::<f64>(b""), 0.0);
+    }
+    #[test]
+    fn pvalue() {
+        // Closed forms of Q(1, x) and Q(2, x), through both branches
+        for x in [0.5_f64, 5.0] {
+            assert!((gamma_q(1.0, x) - (-x).exp()).abs() < 1e-12);
+            assert!((gamma_q(2.0, x) - (1.0 + x) * (-x).exp()).abs() < 1e-12);
+        }
+        assert!((ln_gamma(5.0_f64) - 24.0_f64.ln()).abs() < 1e-12);
+        assert!((ln_gamma(0.5_f64) - core::f64::consts::PI.sqrt().ln()).abs() < 1e-12);
+        // The median of chi-square with 255 degrees of freedom is about 254.3
+        assert!((gamma_q(127.5_f64, 127.5) - 0.488).abs() < 0.005);
+        let random = pseudo_random(1 << 16, 49);
+        let p: f64 = uniformity_pvalue(&random);
+        assert!(p > 0.001 && p <= 1.0);
+        assert!(uniformity_pvalue::<f64>(&vec![0u8; 1 << 16]) < 1e-100);
+        assert_eq!(uniformity_pvalue::<f64>(b""), 1.0);
+    }
 }
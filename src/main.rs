@@ -1,18 +1,46 @@
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+#[cfg(feature = "image")]
+use plotters::coord::Shift;
+#[cfg(feature = "image")]
+use plotters::prelude::*;
+use rgb::RGB8;
 use std::fs::File;
-use std::io::BufReader;
-use std::io::prelude::*;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Seek, SeekFrom};
+use std::ops::Deref;
 use std::process;
-use textplots::{Chart, Plot, Shape};
+use std::str::FromStr;
+use textplots::{Chart, ColorPlot, Plot, Shape};
 
-use shannon::{detect_edges, entropy, EdgeType};
+use shannon::{
+    BlockEntropy, EdgeType, EntropyAccumulator, EntropyEdge, EntropyStats, detect_edges,
+    percent_of_max_entropy,
+};
+
+/// Series colours used by `--combined`, paired with a name for the legend.
+const PALETTE: [(&str, RGB8); 6] = [
+    ("red", RGB8::new(255, 85, 85)),
+    ("green", RGB8::new(80, 250, 123)),
+    ("blue", RGB8::new(98, 114, 255)),
+    ("yellow", RGB8::new(241, 250, 140)),
+    ("magenta", RGB8::new(255, 121, 198)),
+    ("cyan", RGB8::new(139, 233, 253)),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Plot,
+    Json,
+    Csv,
+    Heatmap,
+}
 
 #[derive(Debug, Parser)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    input_file: String,
-    #[clap(long, short, default_value_t = 1024)]
-    block_size: usize,
+    #[clap(required = true)]
+    input_files: Vec<String>,
+    #[clap(long, short, default_value = "1024")]
+    block_size: BlockSize,
     #[clap(long, default_value_t = 180)]
     width: u32,
     #[clap(long, default_value_t = 100)]
@@ -29,6 +57,63 @@ struct Args {
     low: f32,
     #[clap(long, default_value_t = false)]
     no_table: bool,
+    #[clap(long, value_enum, default_value_t = Format::Plot)]
+    format: Format,
+    #[clap(long, default_value_t = false)]
+    combined: bool,
+    #[clap(long, default_value_t = false)]
+    edges: bool,
+    #[clap(long, default_value_t = false)]
+    summary: bool,
+    #[clap(long, default_value_t = false)]
+    total: bool,
+    #[clap(long, default_value_t = false)]
+    no_color: bool,
+    #[clap(long, default_value_t = false)]
+    threshold_line: bool,
+    #[clap(long, default_value_t = 0)]
+    offset: u64,
+    #[clap(long)]
+    length: Option<u64>,
+    #[clap(long, default_value_t = false)]
+    edge_offsets: bool,
+    #[cfg(feature = "image")]
+    #[clap(long, short)]
+    output: Option<String>,
+}
+
+/// A `--block-size` in bytes, or `auto` to pick one from the input size.
+#[derive(Debug, Clone, Copy)]
+enum BlockSize {
+    Auto,
+    Fixed(usize),
+}
+
+impl FromStr for BlockSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "auto" {
+            return Ok(BlockSize::Auto);
+        }
+        s.parse()
+            .map(BlockSize::Fixed)
+            .map_err(|_| format!("expected a number of bytes or auto, got {s}"))
+    }
+}
+
+/// The parsed arguments with `--block-size` resolved to a number of bytes.
+struct Config {
+    args: Args,
+    block_size: usize,
+}
+
+impl Deref for Config {
+    type Target = Args;
+
+    fn deref(&self) -> &Args {
+        &self.args
+    }
 }
 
 fn main() {
@@ -37,80 +122,679 @@ fn main() {
         println!("Width and Height must be atleast 32.");
         process::exit(1);
     }
-    let f = match File::open(&args.input_file) {
-        Ok(f) => f,
+    if let BlockSize::Fixed(0) = args.block_size {
+        println!("Block size must be atleast 1.");
+        process::exit(1);
+    }
+    if args.summary && matches!(args.format, Format::Json | Format::Csv) {
+        println!("--summary can't be combined with --format json or csv.");
+        process::exit(1);
+    }
+
+    // Inputs are opened first so that `auto` can use their sizes once any
+    // compression has been detected.
+    let input_files = args.input_files.clone();
+    let inputs: Vec<Input> = input_files
+        .iter()
+        .map(|input_file| open(input_file, &args))
+        .collect();
+    let block_size = match args.block_size {
+        BlockSize::Fixed(size) => size,
+        BlockSize::Auto => {
+            let size = auto_block_size(&args, &inputs);
+            if !args.quiet {
+                eprintln!("Using a block size of {size} bytes.");
+            }
+            size
+        }
+    };
+    let args = Config { args, block_size };
+
+    let series: Vec<Analysis> = inputs
+        .into_iter()
+        .map(|input| analyse(input, &args))
+        .collect();
+
+    match args.format {
+        Format::Json => {
+            print_json(&args, &series);
+            return;
+        }
+        Format::Csv => {
+            print_csv(&series);
+            return;
+        }
+        Format::Heatmap => {
+            for analysis in &series {
+                if args.summary {
+                    print_summary(&args, analysis);
+                }
+                print_heatmap(&args, analysis, series.len() > 1);
+            }
+            return;
+        }
+        Format::Plot => {}
+    }
+
+    // A plot written to a file replaces the one in the terminal.
+    #[cfg_attr(not(feature = "image"), allow(unused_mut))]
+    let mut no_plot = args.no_plot;
+    #[cfg(feature = "image")]
+    if let Some(path) = &args.output {
+        export_plot(&args, &series, path);
+        no_plot = true;
+    }
+
+    if args.edges || args.edge_offsets {
+        for analysis in &series {
+            if args.summary {
+                print_summary(&args, analysis);
+            }
+            print_edges(&args, analysis, series.len() > 1);
+        }
+        return;
+    }
+
+    if args.combined {
+        for analysis in &series {
+            print_summary(&args, analysis);
+        }
+        if !no_plot {
+            plot_combined(&args, &series);
+        }
+        for analysis in &series {
+            print_table(&args, analysis, series.len() > 1);
+        }
+    } else {
+        for analysis in &series {
+            print_summary(&args, analysis);
+            if !no_plot {
+                plot(&args, analysis);
+            }
+            print_table(&args, analysis, series.len() > 1);
+        }
+    }
+}
+
+/// Block entropies and whole-file byte counts of one input.
+struct Analysis<'a> {
+    input_file: &'a str,
+    blocks: Vec<(usize, f32)>,
+    totals: EntropyAccumulator,
+}
+
+/// Passes reads through while counting every byte seen.
+struct Tally<R> {
+    inner: R,
+    totals: EntropyAccumulator,
+    progress: Option<Progress>,
+}
+
+impl<R: Read> Read for Tally<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.totals.update(&buf[..len]);
+        if let Some(progress) = &mut self.progress {
+            progress.update(self.totals.len());
+        }
+        Ok(len)
+    }
+}
+
+/// Bytes between progress updates.
+const PROGRESS_INTERVAL: u64 = 16 * 1024 * 1024;
+
+/// Reports the bytes read from an input on stderr.
+struct Progress {
+    input_file: String,
+    total: Option<u64>,
+    reported: u64,
+}
+
+impl Progress {
+    fn update(&mut self, read: u64) {
+        if read < self.reported + PROGRESS_INTERVAL {
+            return;
+        }
+        self.reported = read;
+        match self.total {
+            Some(total) if total > 0 => eprint!(
+                "\r{}: {read} of {total} bytes ({}%)",
+                self.input_file,
+                read * 100 / total
+            ),
+            _ => eprint!("\r{}: {read} bytes", self.input_file),
+        }
+    }
+
+    /// Clears the progress line if anything was printed.
+    fn finish(&self) {
+        if self.reported > 0 {
+            eprint!("\r\x1b[2K");
+        }
+    }
+}
+
+/// Picks a power of two block size giving the largest input roughly `width`
+/// blocks, falling back to 1024 when no input size is known (e.g. stdin or
+/// compressed files).
+fn auto_block_size(args: &Args, inputs: &[Input]) -> usize {
+    match inputs.iter().filter_map(|input| input.len).max() {
+        Some(len) => (len.div_ceil(args.width as u64) as usize)
+            .max(1)
+            .next_power_of_two(),
+        None => 1024,
+    }
+}
+
+/// Compression formats recognised by their magic bytes.
+#[derive(Debug, Clone, Copy)]
+enum Compression {
+    #[cfg(feature = "gzip")]
+    Gzip,
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+/// Detects an enabled compression format from the first bytes of an input.
+#[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+fn compression(magic: &[u8]) -> Option<Compression> {
+    #[cfg(feature = "gzip")]
+    if magic.starts_with(&[0x1f, 0x8b]) {
+        return Some(Compression::Gzip);
+    }
+    #[cfg(feature = "zstd")]
+    if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        return Some(Compression::Zstd);
+    }
+    None
+}
+
+#[cfg_attr(not(any(feature = "gzip", feature = "zstd")), allow(unused_variables))]
+fn decoder(compression: Compression, reader: Box<dyn BufRead>) -> io::Result<Box<dyn Read>> {
+    match compression {
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(flate2::bufread::MultiGzDecoder::new(reader))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => match ruzstd::decoding::StreamingDecoder::new(reader) {
+            Ok(decoder) => Ok(Box::new(decoder)),
+            Err(e) => Err(io::Error::other(e)),
+        },
+    }
+}
+
+/// Opens an input, decompressing it if it is in an enabled compression
+/// format, and skips to `offset` in the (decompressed) data.
+///
+/// Also returns the number of bytes left to read, when this is known up
+/// front, i.e. for uncompressed files.
+fn open_input(input_file: &str, offset: u64) -> io::Result<(Box<dyn Read>, Option<u64>)> {
+    let mut reader: Box<dyn BufRead> = if input_file == "-" {
+        Box::new(BufReader::new(io::stdin()))
+    } else {
+        let file = File::open(input_file)?;
+        let len = file.metadata()?.len();
+        let mut f = BufReader::new(file);
+        if compression(f.fill_buf()?).is_none() {
+            f.seek(SeekFrom::Start(offset))?;
+            return Ok((Box::new(f), Some(len.saturating_sub(offset))));
+        }
+        Box::new(f)
+    };
+    let mut reader: Box<dyn Read> = match compression(reader.fill_buf()?) {
+        Some(format) => decoder(format, reader)?,
+        None => reader,
+    };
+    // Standard input and decompressed data can't seek, so read up to the
+    // offset and discard it.
+    io::copy(&mut (&mut reader).take(offset), &mut io::sink())?;
+    Ok((reader, None))
+}
+
+/// An opened input, limited to `--length` bytes.
+struct Input<'a> {
+    input_file: &'a str,
+    reader: Box<dyn Read>,
+    /// Number of bytes that will be analysed, if known up front
+    len: Option<u64>,
+}
+
+fn open<'a>(input_file: &'a str, args: &Args) -> Input<'a> {
+    let (reader, remaining) = match open_input(input_file, args.offset) {
+        Ok(opened) => opened,
         Err(e) => {
-            println!("Couldn't open file {} got error {e}.", args.input_file);
+            println!("Couldn't open file {input_file} got error {e}.");
             process::exit(1);
         }
     };
-    let mut reader = BufReader::new(f);
+    let reader = match args.length {
+        Some(length) => Box::new(reader.take(length)),
+        None => reader,
+    };
+    Input {
+        input_file,
+        reader,
+        len: match (remaining, args.length) {
+            (Some(remaining), Some(length)) => Some(remaining.min(length)),
+            (remaining, _) => remaining,
+        },
+    }
+}
 
-    let mut read_buffer = vec![0u8; args.block_size];
+fn analyse<'a>(input: Input<'a>, args: &Config) -> Analysis<'a> {
+    let input_file = input.input_file;
+    let progress = (!args.quiet && io::stderr().is_terminal()).then(|| Progress {
+        input_file: input_file.to_string(),
+        total: input.len,
+        reported: 0,
+    });
+    let mut tally = Tally {
+        inner: input.reader,
+        totals: EntropyAccumulator::new(),
+        progress,
+    };
     let mut s = Vec::new();
-    loop {
-        let len = match reader.read(&mut read_buffer) {
-            Ok(l) => l,
+    for block in BlockEntropy::<_, f32>::new(&mut tally, args.block_size) {
+        match block {
+            Ok(value) => s.push(value),
             Err(e) => {
-                println!(
-                    "Unexpecedtly could not read from file {}, got error {e}",
-                    args.input_file
-                );
+                println!("Unexpecedtly could not read from file {input_file}, got error {e}");
                 process::exit(1);
             }
-        };
-        if len == 0 {
-            break;
         }
-        s.push(entropy::<f32>(&read_buffer[..len]));
     }
-    let s_avg: f32 = s.iter().sum::<f32>() / (s.len() as f32);
-    let x_max = s.len() as f32;
-    let y_max = args
-        .y_max
-        .unwrap_or_else(|| s.iter().fold(0.0_f32, |a, &y| a.max(y)));
-
-    let indexed_s: Vec<(usize, f32)> = s.iter().copied().enumerate().collect();
-
-    let s: Vec<(f32, f32)> = s
-        .into_iter()
-        .enumerate()
-        .map(|(x, y)| (x as f32, y))
-        .collect();
+    if let Some(progress) = &tally.progress {
+        progress.finish();
+    }
+    Analysis {
+        input_file,
+        blocks: s,
+        totals: tally.totals,
+    }
+}
 
+fn print_summary(args: &Config, analysis: &Analysis) {
+    let values = &analysis.blocks;
+    let s_avg: f32 = values.iter().map(|&(_, e)| e).sum::<f32>() / (values.len() as f32);
     if !args.quiet {
         println!(
             "Analysed {} as {} chunks of length {}, average entropy per byte was {s_avg:.1} bits.",
-            args.input_file,
-            s.len(),
+            analysis.input_file,
+            values.len(),
             args.block_size
         );
     }
+    if args.summary {
+        let totals = &analysis.totals;
+        let e: f64 = totals.finalize();
+        let distinct = totals.counts().iter().filter(|&&count| count > 0).count();
+        let stats = EntropyStats::from_values(values);
+        println!("File:               {}", analysis.input_file);
+        if args.offset > 0 || args.length.is_some() {
+            println!(
+                "Bytes analysed:     {} bytes ({}..{})",
+                totals.len(),
+                args.offset,
+                args.offset + totals.len()
+            );
+        } else {
+            println!("File size:          {} bytes", totals.len());
+        }
+        println!(
+            "Entropy:            {e:.6} bits per byte ({:.1}% of maximum)",
+            percent_of_max_entropy(e)
+        );
+        println!("Total entropy:      {:.1} bits", e * totals.len() as f64);
+        println!("Distinct bytes:     {distinct}");
+        if !values.is_empty() {
+            println!(
+                "Block entropy:      min {:.6}, max {:.6}, mean {:.6}, median {:.6}, std dev {:.6}",
+                stats.min, stats.max, stats.mean, stats.median, stats.std_dev
+            );
+        }
+    }
+}
+
+/// The series to plot: bits per byte, or bits per block with `--total`.
+fn plotted(args: &Config, analysis: &Analysis) -> Vec<(usize, f32)> {
+    if !args.total {
+        return analysis.blocks.clone();
+    }
+    let file_len = analysis.totals.len() as usize;
+    analysis
+        .blocks
+        .iter()
+        .map(|&(block, e)| {
+            let len = (file_len - block * args.block_size).min(args.block_size);
+            (block, e * len as f32)
+        })
+        .collect()
+}
+
+fn y_max(args: &Config, values: &[(usize, f32)]) -> f32 {
+    args.y_max
+        .unwrap_or_else(|| values.iter().fold(0.0_f32, |a, &(_, y)| a.max(y)))
+}
+
+fn points(values: &[(usize, f32)]) -> Vec<(f32, f32)> {
+    values.iter().map(|&(x, y)| (x as f32, y)).collect()
+}
+
+fn plot(args: &Config, analysis: &Analysis) {
+    let values = plotted(args, analysis);
+    let x_max = values.len() as f32;
+    let colored = !args.no_color && io::stdout().is_terminal();
+    let s = points(&values);
+    let all_bars = Shape::Bars(&s);
+    // One bar per block so that each can take its own colour.
+    let bars: Vec<[(f32, f32); 2]> = values
+        .iter()
+        .filter(|_| colored)
+        .map(|&(x, y)| [(x as f32, y), (x as f32 + 1.0, y)])
+        .collect();
+    let shapes: Vec<Shape> = bars.iter().map(|bar| Shape::Bars(bar)).collect();
+    let lines = threshold_lines(args, x_max);
+    let lines: Vec<Shape> = lines.iter().map(|line| Shape::Lines(line)).collect();
+
+    let mut chart = Chart::new_with_y_range(
+        args.width,
+        args.height,
+        0.0,
+        x_max,
+        0.0,
+        y_max(args, &values),
+    );
+    let mut chart = &mut chart;
+    if colored {
+        for (shape, &(_, e)) in shapes.iter().zip(&analysis.blocks) {
+            chart = chart.linecolorplot(shape, entropy_color(e));
+        }
+    } else {
+        chart = chart.lineplot(&all_bars);
+    }
+    for line in &lines {
+        chart = chart.lineplot(line);
+    }
+    chart.display();
+}
+
+/// Horizontal lines at the `--high` and `--low` thresholds, in plotted units.
+fn threshold_lines(args: &Config, x_max: f32) -> Vec<[(f32, f32); 2]> {
+    if !args.threshold_line {
+        return Vec::new();
+    }
+    let scale = if args.total {
+        8.0 * args.block_size as f32
+    } else {
+        8.0
+    };
+    [args.high, args.low]
+        .iter()
+        .map(|threshold| [(0.0, threshold * scale), (x_max, threshold * scale)])
+        .collect()
+}
+
+/// Shades from green for 0 bits per byte to red for 8 bits per byte.
+fn entropy_color(entropy: f32) -> RGB8 {
+    let t = (entropy / 8.0).clamp(0.0, 1.0);
+    let (low, high) = (PALETTE[1].1, PALETTE[0].1);
+    let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+    RGB8::new(mix(low.r, high.r), mix(low.g, high.g), mix(low.b, high.b))
+}
+
+/// Shading characters for the heatmap, from 0 to 8 bits per byte.
+const SHADES: [char; 5] = [' ', '░', '▒', '▓', '█'];
+
+/// Prints block entropies as rows of `--width` shaded cells, each row
+/// prefixed by the offset of its first block.
+fn print_heatmap(args: &Config, analysis: &Analysis, labelled: bool) {
+    if labelled {
+        println!("{}", analysis.input_file);
+    }
+    let colored = !args.no_color && io::stdout().is_terminal();
+    let row_len = args.width as usize;
+    for (row, blocks) in analysis.blocks.chunks(row_len).enumerate() {
+        let offset = args.offset + row as u64 * row_len as u64 * args.block_size as u64;
+        let mut line = format!("{offset:#010X} ");
+        for &(_, e) in blocks {
+            let level = ((e / 8.0).clamp(0.0, 1.0) * (SHADES.len() - 1) as f32).round();
+            let shade = SHADES[level as usize];
+            if colored {
+                let RGB8 { r, g, b } = entropy_color(e);
+                line.push_str(&format!("\x1b[38;2;{r};{g};{b}m{shade}"));
+            } else {
+                line.push(shade);
+            }
+        }
+        if colored {
+            line.push_str("\x1b[0m");
+        }
+        println!("{line}");
+    }
+}
+
+fn plot_combined(args: &Config, series: &[Analysis]) {
+    let values: Vec<Vec<(usize, f32)>> = series.iter().map(|a| plotted(args, a)).collect();
+    let x_max = values.iter().map(Vec::len).max().unwrap_or(0) as f32;
+    let y_max = values
+        .iter()
+        .map(|v| y_max(args, v))
+        .fold(0.0_f32, f32::max);
+    let points: Vec<Vec<(f32, f32)>> = values.iter().map(|v| points(v)).collect();
+    let shapes: Vec<Shape> = points.iter().map(|p| Shape::Lines(p)).collect();
+    let lines = threshold_lines(args, x_max);
+    let lines: Vec<Shape> = lines.iter().map(|line| Shape::Lines(line)).collect();
+
+    let mut chart = Chart::new_with_y_range(args.width, args.height, 0.0, x_max, 0.0, y_max);
+    let mut chart = &mut chart;
+    for (i, shape) in shapes.iter().enumerate() {
+        chart = chart.linecolorplot(shape, PALETTE[i % PALETTE.len()].1);
+    }
+    for line in &lines {
+        chart = chart.lineplot(line);
+    }
+    chart.display();
 
-    if !args.no_plot {
-        Chart::new_with_y_range(args.width, args.height, 0.0, x_max, 0.0, y_max)
-            .lineplot(&Shape::Bars(&s))
-            .display();
+    for (i, analysis) in series.iter().enumerate() {
+        println!(
+            "{:<8} {}",
+            PALETTE[i % PALETTE.len()].0,
+            analysis.input_file
+        );
     }
+}
 
-    if !args.no_table {
-        let edges = detect_edges(&indexed_s, args.high, args.low);
-        if !edges.is_empty() {
-            println!("DECIMAL       HEXADECIMAL      ENTROPY");
+/// Size in pixels of plots written with `--output`.
+#[cfg(feature = "image")]
+const IMAGE_SIZE: (u32, u32) = (1280, 480);
+
+/// Writes every series to an SVG file if `path` ends in `.svg`, otherwise to
+/// a bitmap in the format given by its extension (e.g. PNG).
+#[cfg(feature = "image")]
+fn export_plot(args: &Config, series: &[Analysis], path: &str) {
+    let result = if path.ends_with(".svg") {
+        draw_plot(
+            args,
+            series,
+            SVGBackend::new(path, IMAGE_SIZE).into_drawing_area(),
+        )
+    } else {
+        draw_plot(
+            args,
+            series,
+            BitMapBackend::new(path, IMAGE_SIZE).into_drawing_area(),
+        )
+    };
+    if let Err(e) = result {
+        println!("Couldn't write plot {path} got error {e}.");
+        process::exit(1);
+    }
+    if series.len() > 1 {
+        for (i, analysis) in series.iter().enumerate() {
             println!(
-                "--------------------------------------------------------------------------------"
+                "{:<8} {}",
+                PALETTE[i % PALETTE.len()].0,
+                analysis.input_file
             );
-            for edge in edges {
-                let offset = edge.block_index * args.block_size;
+        }
+    }
+}
+
+/// Draws each series as a line in its palette colour on a grid. No font
+/// backend is enabled, so the axes are unlabelled.
+#[cfg(feature = "image")]
+fn draw_plot<DB: DrawingBackend>(
+    args: &Config,
+    series: &[Analysis],
+    root: DrawingArea<DB, Shift>,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    DB::ErrorType: 'static,
+{
+    let values: Vec<Vec<(usize, f32)>> = series.iter().map(|a| plotted(args, a)).collect();
+    let x_max = (values.iter().map(Vec::len).max().unwrap_or(0) as f32).max(1.0);
+    let y_max = values
+        .iter()
+        .map(|v| y_max(args, v))
+        .fold(0.0_f32, f32::max)
+        .max(f32::MIN_POSITIVE);
+
+    root.fill(&WHITE)?;
+    let mut chart = ChartBuilder::on(&root)
+        .margin(10)
+        .build_cartesian_2d(0.0..x_max, 0.0..y_max)?;
+    chart.configure_mesh().x_labels(10).y_labels(9).draw()?;
+    for (i, v) in values.iter().enumerate() {
+        let rgb = PALETTE[i % PALETTE.len()].1;
+        let color = RGBColor(rgb.r, rgb.g, rgb.b);
+        chart.draw_series(LineSeries::new(points(v), &color))?;
+    }
+    for line in threshold_lines(args, x_max) {
+        chart.draw_series(LineSeries::new(line, &BLACK))?;
+    }
+    root.present()?;
+    Ok(())
+}
+
+fn print_table(args: &Config, analysis: &Analysis, labelled: bool) {
+    if args.no_table {
+        return;
+    }
+    let edges = detect_edges(&analysis.blocks, args.high, args.low);
+    if edges.is_empty() {
+        return;
+    }
+    if labelled {
+        println!("{}", analysis.input_file);
+    }
+    println!("DECIMAL       HEXADECIMAL      ENTROPY");
+    println!("--------------------------------------------------------------------------------");
+    for edge in edges {
+        let offset = args.offset + edge.byte_offset(args.block_size) as u64;
+        println!(
+            "{offset:<7}       {offset:#010X}       {} entropy edge ({:.6})",
+            edge.edge_type, edge.entropy
+        );
+    }
+}
+
+fn print_edges(args: &Config, analysis: &Analysis, labelled: bool) {
+    if labelled {
+        println!("{}", analysis.input_file);
+    }
+    let heading = if args.edge_offsets { "OFFSET" } else { "BLOCK" };
+    println!("{heading:<14}EDGE             ENTROPY");
+    println!("--------------------------------------------------------------------------------");
+    for edge in detect_edges(&analysis.blocks, args.high, args.low) {
+        let position = if args.edge_offsets {
+            args.offset + edge.byte_offset(args.block_size) as u64
+        } else {
+            edge.block_index as u64
+        };
+        println!(
+            "{position:<7}       {:<7}          {:.6}",
+            edge.edge_type, edge.entropy
+        );
+    }
+}
+
+fn print_json(args: &Config, series: &[Analysis]) {
+    if let [analysis] = series {
+        print_json_blocks(args, &analysis.blocks, "");
+        println!();
+        return;
+    }
+    println!("{{");
+    for (i, analysis) in series.iter().enumerate() {
+        print!("  {}: ", json_string(analysis.input_file));
+        print_json_blocks(args, &analysis.blocks, "  ");
+        println!("{}", if i + 1 < series.len() { "," } else { "" });
+    }
+    println!("}}");
+}
+
+fn print_json_blocks(args: &Config, values: &[(usize, f32)], indent: &str) {
+    let edges: Vec<EntropyEdge<f32>> = if args.no_table {
+        Vec::new()
+    } else {
+        detect_edges(values, args.high, args.low)
+    };
+    let mut edges = edges.iter().peekable();
+    println!("[");
+    for (i, &(block, entropy)) in values.iter().enumerate() {
+        let separator = if i + 1 < values.len() { "," } else { "" };
+        match edges.next_if(|edge| edge.block_index == block) {
+            Some(edge) => {
                 let label = match edge.edge_type {
-                    EdgeType::Rising => "Rising",
-                    EdgeType::Falling => "Falling",
+                    EdgeType::Rising => "rising",
+                    EdgeType::Falling => "falling",
                 };
                 println!(
-                    "{offset:<7}       {offset:#010X}       {label} entropy edge ({:.6})",
-                    edge.entropy
+                    "{indent}  {{\"block\": {block}, \"entropy\": {entropy}, \"edge\": \"{label}\"}}{separator}"
                 );
             }
+            None => {
+                println!("{indent}  {{\"block\": {block}, \"entropy\": {entropy}}}{separator}")
+            }
         }
     }
+    print!("{indent}]");
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn print_csv(series: &[Analysis]) {
+    if let [analysis] = series {
+        println!("block_index,entropy");
+        for (block, entropy) in &analysis.blocks {
+            println!("{block},{entropy}");
+        }
+        return;
+    }
+    println!("file,block_index,entropy");
+    for analysis in series {
+        for (block, entropy) in &analysis.blocks {
+            println!("{},{block},{entropy}", csv_field(analysis.input_file));
+        }
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
 }
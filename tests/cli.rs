@@ -0,0 +1,53 @@
+//! End to end tests of the `shannon` binary.
+
+#![cfg(feature = "gzip")]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+
+/// Writes `data` to a file in the temporary directory.
+fn temp_file(name: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("shannon-cli-{}-{name}", std::process::id()));
+    fs::write(&path, data).unwrap();
+    path
+}
+
+/// Runs the binary, returning its stdout and stderr.
+fn shannon(args: &[&str]) -> (String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_shannon"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn auto_block_size_of_gzip_input() {
+    let zeros = vec![0u8; 4_000_000];
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&zeros).unwrap();
+    let raw = temp_file("zeros.bin", &zeros);
+    let gzipped = temp_file("zeros.bin.gz", &encoder.finish().unwrap());
+
+    let args = ["--block-size", "auto", "--no-plot", "--no-table"];
+    let (stdout, stderr) = shannon(&[&[raw.to_str().unwrap()][..], &args].concat());
+    assert!(stderr.contains("Using a block size of 32768 bytes."));
+    assert!(stdout.contains("as 123 chunks of length 32768"));
+    // The compressed size says nothing about the decompressed size, so the
+    // default is used as for stdin.
+    let (stdout, stderr) = shannon(&[&[gzipped.to_str().unwrap()][..], &args].concat());
+    assert!(stderr.contains("Using a block size of 1024 bytes."));
+    assert!(stdout.contains("as 3907 chunks of length 1024"));
+
+    fs::remove_file(raw).unwrap();
+    fs::remove_file(gzipped).unwrap();
+}